@@ -0,0 +1,311 @@
+//! Parsing of CSS colors, as used by presentation attributes like `fill`.
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+/// An sRGB color with an alpha channel.
+///
+/// Requires the `color` feature.
+pub struct Color {
+    /// The red channel
+    pub r: u8,
+    /// The green channel
+    pub g: u8,
+    /// The blue channel
+    pub b: u8,
+    /// The alpha channel, where 255 is fully opaque
+    pub a: u8,
+}
+
+impl Color {
+    /// Create an opaque color
+    #[must_use]
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Color {
+        Color { r, g, b, a: 255 }
+    }
+
+    /// Parse a CSS color.
+    ///
+    /// Supports hex notation (`#rgb`, `#rgba`, `#rrggbb`, `#rrggbbaa`),
+    /// `rgb()` and `rgba()` with numbers or percentages, and named colors
+    /// (e.g. `red` or `transparent`). Returns `None` for anything else,
+    /// including values like `none`, `currentColor`, or `url(#gradient)`
+    /// which don't describe a single color.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Color;
+    ///
+    /// assert_eq!(Color::parse("#f00"), Some(Color::rgb(255, 0, 0)));
+    /// assert_eq!(Color::parse("rgb(0 0 255 / 50%)").unwrap().a, 128);
+    /// assert_eq!(Color::parse("none"), None);
+    /// ```
+    #[must_use]
+    pub fn parse(s: &str) -> Option<Color> {
+        let s = s.trim();
+        if let Some(hex) = s.strip_prefix('#') {
+            return parse_hex(hex);
+        }
+        let lower = s.to_ascii_lowercase();
+        if let Some(args) = lower
+            .strip_prefix("rgba(")
+            .or_else(|| lower.strip_prefix("rgb("))
+        {
+            return parse_rgb_function(args.strip_suffix(')')?);
+        }
+        named(&lower)
+    }
+}
+
+impl fmt::Display for Color {
+    /// Formats the color in hex notation, e.g. `#ff0000`.
+    /// The alpha channel is only included if the color isn't opaque.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)?;
+        if self.a != 255 {
+            write!(f, "{:02x}", self.a)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse the digits of a hex color
+fn parse_hex(hex: &str) -> Option<Color> {
+    if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let digit = |i: usize| u8::from_str_radix(&hex[i..=i], 16).ok();
+    let pair = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    match hex.len() {
+        3 | 4 => Some(Color {
+            r: digit(0)? * 17,
+            g: digit(1)? * 17,
+            b: digit(2)? * 17,
+            a: if hex.len() == 4 { digit(3)? * 17 } else { 255 },
+        }),
+        6 | 8 => Some(Color {
+            r: pair(0)?,
+            g: pair(2)?,
+            b: pair(4)?,
+            a: if hex.len() == 8 { pair(6)? } else { 255 },
+        }),
+        _ => None,
+    }
+}
+
+/// Parse the arguments of `rgb()` or `rgba()`, separated by commas
+/// (`255, 0, 0, 0.5`) or by spaces (`255 0 0 / 50%`)
+fn parse_rgb_function(args: &str) -> Option<Color> {
+    let args: Vec<&str> = if args.contains(',') {
+        args.split(',').map(str::trim).collect()
+    } else {
+        let (channels, alpha) = match args.split_once('/') {
+            Some((channels, alpha)) => (channels, Some(alpha.trim())),
+            None => (args, None),
+        };
+        channels.split_whitespace().chain(alpha).collect()
+    };
+    match args[..] {
+        [r, g, b] => Some(Color::rgb(channel(r)?, channel(g)?, channel(b)?)),
+        [r, g, b, a] => Some(Color {
+            a: alpha(a)?,
+            ..Color::rgb(channel(r)?, channel(g)?, channel(b)?)
+        }),
+        _ => None,
+    }
+}
+
+/// Parse a color channel, either a number from 0 to 255 or a percentage
+fn channel(s: &str) -> Option<u8> {
+    match s.strip_suffix('%') {
+        Some(percent) => to_u8(percent.parse::<f64>().ok()? / 100.0 * 255.0),
+        None => to_u8(s.parse::<f64>().ok()?),
+    }
+}
+
+/// Parse an alpha value, either a number from 0 to 1 or a percentage
+fn alpha(s: &str) -> Option<u8> {
+    match s.strip_suffix('%') {
+        Some(percent) => to_u8(percent.parse::<f64>().ok()? / 100.0 * 255.0),
+        None => to_u8(s.parse::<f64>().ok()? * 255.0),
+    }
+}
+
+/// Round and clamp a value to the range of a channel
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+const fn to_u8(value: f64) -> Option<u8> {
+    if value.is_nan() {
+        return None;
+    }
+    // Out of range values are clamped, as CSS requires
+    Some(value.round().clamp(0.0, 255.0) as u8)
+}
+
+/// Look up a named color (in lowercase)
+fn named(name: &str) -> Option<Color> {
+    if name == "transparent" {
+        return Some(Color {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 0,
+        });
+    }
+    let index = NAMED_COLORS
+        .binary_search_by_key(&name, |(name, _)| name)
+        .ok()?;
+    let [r, g, b] = NAMED_COLORS[index].1;
+    Some(Color::rgb(r, g, b))
+}
+
+/// The CSS named colors, sorted by name
+const NAMED_COLORS: [(&str, [u8; 3]); 148] = [
+    ("aliceblue", [240, 248, 255]),
+    ("antiquewhite", [250, 235, 215]),
+    ("aqua", [0, 255, 255]),
+    ("aquamarine", [127, 255, 212]),
+    ("azure", [240, 255, 255]),
+    ("beige", [245, 245, 220]),
+    ("bisque", [255, 228, 196]),
+    ("black", [0, 0, 0]),
+    ("blanchedalmond", [255, 235, 205]),
+    ("blue", [0, 0, 255]),
+    ("blueviolet", [138, 43, 226]),
+    ("brown", [165, 42, 42]),
+    ("burlywood", [222, 184, 135]),
+    ("cadetblue", [95, 158, 160]),
+    ("chartreuse", [127, 255, 0]),
+    ("chocolate", [210, 105, 30]),
+    ("coral", [255, 127, 80]),
+    ("cornflowerblue", [100, 149, 237]),
+    ("cornsilk", [255, 248, 220]),
+    ("crimson", [220, 20, 60]),
+    ("cyan", [0, 255, 255]),
+    ("darkblue", [0, 0, 139]),
+    ("darkcyan", [0, 139, 139]),
+    ("darkgoldenrod", [184, 134, 11]),
+    ("darkgray", [169, 169, 169]),
+    ("darkgreen", [0, 100, 0]),
+    ("darkgrey", [169, 169, 169]),
+    ("darkkhaki", [189, 183, 107]),
+    ("darkmagenta", [139, 0, 139]),
+    ("darkolivegreen", [85, 107, 47]),
+    ("darkorange", [255, 140, 0]),
+    ("darkorchid", [153, 50, 204]),
+    ("darkred", [139, 0, 0]),
+    ("darksalmon", [233, 150, 122]),
+    ("darkseagreen", [143, 188, 143]),
+    ("darkslateblue", [72, 61, 139]),
+    ("darkslategray", [47, 79, 79]),
+    ("darkslategrey", [47, 79, 79]),
+    ("darkturquoise", [0, 206, 209]),
+    ("darkviolet", [148, 0, 211]),
+    ("deeppink", [255, 20, 147]),
+    ("deepskyblue", [0, 191, 255]),
+    ("dimgray", [105, 105, 105]),
+    ("dimgrey", [105, 105, 105]),
+    ("dodgerblue", [30, 144, 255]),
+    ("firebrick", [178, 34, 34]),
+    ("floralwhite", [255, 250, 240]),
+    ("forestgreen", [34, 139, 34]),
+    ("fuchsia", [255, 0, 255]),
+    ("gainsboro", [220, 220, 220]),
+    ("ghostwhite", [248, 248, 255]),
+    ("gold", [255, 215, 0]),
+    ("goldenrod", [218, 165, 32]),
+    ("gray", [128, 128, 128]),
+    ("green", [0, 128, 0]),
+    ("greenyellow", [173, 255, 47]),
+    ("grey", [128, 128, 128]),
+    ("honeydew", [240, 255, 240]),
+    ("hotpink", [255, 105, 180]),
+    ("indianred", [205, 92, 92]),
+    ("indigo", [75, 0, 130]),
+    ("ivory", [255, 255, 240]),
+    ("khaki", [240, 230, 140]),
+    ("lavender", [230, 230, 250]),
+    ("lavenderblush", [255, 240, 245]),
+    ("lawngreen", [124, 252, 0]),
+    ("lemonchiffon", [255, 250, 205]),
+    ("lightblue", [173, 216, 230]),
+    ("lightcoral", [240, 128, 128]),
+    ("lightcyan", [224, 255, 255]),
+    ("lightgoldenrodyellow", [250, 250, 210]),
+    ("lightgray", [211, 211, 211]),
+    ("lightgreen", [144, 238, 144]),
+    ("lightgrey", [211, 211, 211]),
+    ("lightpink", [255, 182, 193]),
+    ("lightsalmon", [255, 160, 122]),
+    ("lightseagreen", [32, 178, 170]),
+    ("lightskyblue", [135, 206, 250]),
+    ("lightslategray", [119, 136, 153]),
+    ("lightslategrey", [119, 136, 153]),
+    ("lightsteelblue", [176, 196, 222]),
+    ("lightyellow", [255, 255, 224]),
+    ("lime", [0, 255, 0]),
+    ("limegreen", [50, 205, 50]),
+    ("linen", [250, 240, 230]),
+    ("magenta", [255, 0, 255]),
+    ("maroon", [128, 0, 0]),
+    ("mediumaquamarine", [102, 205, 170]),
+    ("mediumblue", [0, 0, 205]),
+    ("mediumorchid", [186, 85, 211]),
+    ("mediumpurple", [147, 112, 219]),
+    ("mediumseagreen", [60, 179, 113]),
+    ("mediumslateblue", [123, 104, 238]),
+    ("mediumspringgreen", [0, 250, 154]),
+    ("mediumturquoise", [72, 209, 204]),
+    ("mediumvioletred", [199, 21, 133]),
+    ("midnightblue", [25, 25, 112]),
+    ("mintcream", [245, 255, 250]),
+    ("mistyrose", [255, 228, 225]),
+    ("moccasin", [255, 228, 181]),
+    ("navajowhite", [255, 222, 173]),
+    ("navy", [0, 0, 128]),
+    ("oldlace", [253, 245, 230]),
+    ("olive", [128, 128, 0]),
+    ("olivedrab", [107, 142, 35]),
+    ("orange", [255, 165, 0]),
+    ("orangered", [255, 69, 0]),
+    ("orchid", [218, 112, 214]),
+    ("palegoldenrod", [238, 232, 170]),
+    ("palegreen", [152, 251, 152]),
+    ("paleturquoise", [175, 238, 238]),
+    ("palevioletred", [219, 112, 147]),
+    ("papayawhip", [255, 239, 213]),
+    ("peachpuff", [255, 218, 185]),
+    ("peru", [205, 133, 63]),
+    ("pink", [255, 192, 203]),
+    ("plum", [221, 160, 221]),
+    ("powderblue", [176, 224, 230]),
+    ("purple", [128, 0, 128]),
+    ("rebeccapurple", [102, 51, 153]),
+    ("red", [255, 0, 0]),
+    ("rosybrown", [188, 143, 143]),
+    ("royalblue", [65, 105, 225]),
+    ("saddlebrown", [139, 69, 19]),
+    ("salmon", [250, 128, 114]),
+    ("sandybrown", [244, 164, 96]),
+    ("seagreen", [46, 139, 87]),
+    ("seashell", [255, 245, 238]),
+    ("sienna", [160, 82, 45]),
+    ("silver", [192, 192, 192]),
+    ("skyblue", [135, 206, 235]),
+    ("slateblue", [106, 90, 205]),
+    ("slategray", [112, 128, 144]),
+    ("slategrey", [112, 128, 144]),
+    ("snow", [255, 250, 250]),
+    ("springgreen", [0, 255, 127]),
+    ("steelblue", [70, 130, 180]),
+    ("tan", [210, 180, 140]),
+    ("teal", [0, 128, 128]),
+    ("thistle", [216, 191, 216]),
+    ("tomato", [255, 99, 71]),
+    ("turquoise", [64, 224, 208]),
+    ("violet", [238, 130, 238]),
+    ("wheat", [245, 222, 179]),
+    ("white", [255, 255, 255]),
+    ("whitesmoke", [245, 245, 245]),
+    ("yellow", [255, 255, 0]),
+    ("yellowgreen", [154, 205, 50]),
+];
@@ -0,0 +1,185 @@
+//! Conversion of raw bytes into text which can be parsed.
+use crate::MetadataError;
+
+/// Decode SVG data into a string.
+///
+/// With the `encoding` feature, the encoding is taken from a byte order
+/// mark or from the XML declaration (e.g. `encoding="windows-1252"`),
+/// falling back to UTF-8. Without it, the data must be UTF-8.
+#[cfg(feature = "encoding")]
+pub(crate) fn decode(data: Vec<u8>) -> Result<String, MetadataError> {
+    let bom = encoding_rs::Encoding::for_bom(&data).map(|(encoding, _)| encoding);
+    let encoding = bom
+        .or_else(|| declared_encoding(&data))
+        .unwrap_or(encoding_rs::UTF_8);
+    if encoding == encoding_rs::UTF_8 && bom.is_none() {
+        // No need to copy the data
+        return Ok(String::from_utf8(data)?);
+    }
+
+    // Removes the byte order mark if there is one
+    let (text, _, had_errors) = encoding.decode(&data);
+    if had_errors {
+        return Err(MetadataError::new(&format!(
+            "Invalid {} data",
+            encoding.name()
+        )));
+    }
+    Ok(text.into_owned())
+}
+
+/// Decode SVG data into a string.
+///
+/// Enable the `encoding` feature to support encodings other than UTF-8.
+#[cfg(not(feature = "encoding"))]
+pub(crate) fn decode(data: Vec<u8>) -> Result<String, MetadataError> {
+    Ok(String::from_utf8(data)?)
+}
+
+/// The first two bytes of gzip-compressed data (e.g. `.svgz` files)
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Decompress gzip-compressed SVG data. Uncompressed data is returned
+/// unchanged.
+#[cfg(feature = "svgz")]
+pub(crate) fn decompress(data: Vec<u8>) -> Result<Vec<u8>, MetadataError> {
+    use std::io::Read;
+
+    if !data.starts_with(&GZIP_MAGIC) {
+        return Ok(data);
+    }
+    let mut decompressed = Vec::new();
+    flate2::read::GzDecoder::new(data.as_slice()).read_to_end(&mut decompressed)?;
+    Ok(decompressed)
+}
+
+/// Decompress gzip-compressed SVG data. Uncompressed data is returned
+/// unchanged.
+///
+/// Enable the `svgz` feature to support compressed data.
+#[cfg(not(feature = "svgz"))]
+pub(crate) fn decompress(data: Vec<u8>) -> Result<Vec<u8>, MetadataError> {
+    if data.starts_with(&GZIP_MAGIC) {
+        return Err(MetadataError::new(
+            "Compressed SVG files require the `svgz` feature",
+        ));
+    }
+    Ok(data)
+}
+
+/// Read the encoding from the XML declaration, if there is one
+#[cfg(feature = "encoding")]
+fn declared_encoding(data: &[u8]) -> Option<&'static encoding_rs::Encoding> {
+    use once_cell::sync::Lazy;
+    use regex::bytes::Regex;
+
+    /// Regex to extract the encoding from an XML declaration
+    /// Both quote styles are allowed, but they have to match.
+    static DECLARED_ENCODING: Lazy<Regex> = Lazy::new(|| {
+        let label = "[A-Za-z0-9._:-]+";
+        Regex::new(&format!(
+            r#"^<\?xml[^>]*?\sencoding\s*=\s*(?:"({label})"|'({label})')"#
+        ))
+        .unwrap()
+    });
+
+    let caps = DECLARED_ENCODING.captures(data)?;
+    let label = caps.get(1).or_else(|| caps.get(2))?.as_bytes();
+    encoding_rs::Encoding::for_label(label)
+}
+
+/// Extract the payload of a `data:image/svg+xml` URI, decoding base64 or
+/// percent-encoded data
+pub(crate) fn data_uri(uri: &str) -> Result<Vec<u8>, MetadataError> {
+    let uri = uri.trim();
+    // The scheme is case-insensitive
+    let rest = uri
+        .get(..5)
+        .filter(|scheme| scheme.eq_ignore_ascii_case("data:"))
+        .map(|_| &uri[5..])
+        .ok_or_else(|| MetadataError::new("Not a data URI: missing `data:` prefix"))?;
+    let (header, payload) = rest
+        .split_once(',')
+        .ok_or_else(|| MetadataError::new("Invalid data URI: missing `,` before the data"))?;
+
+    let mut params = header.split(';');
+    let mime_type = params.next().unwrap_or_default().trim();
+    if !mime_type.eq_ignore_ascii_case("image/svg+xml") {
+        return Err(MetadataError::new(&format!(
+            "Unsupported data URI type `{mime_type}`, expected `image/svg+xml`"
+        )));
+    }
+    let is_base64 = params.any(|param| param.trim().eq_ignore_ascii_case("base64"));
+
+    // Base64 payloads can be percent-encoded as well, e.g. `=` as `%3D`
+    let bytes = percent_decode(payload)?;
+    if is_base64 {
+        base64_decode(&bytes)
+    } else {
+        Ok(bytes)
+    }
+}
+
+/// Decode `%XX` escapes. Everything else is taken as is.
+fn percent_decode(s: &str) -> Result<Vec<u8>, MetadataError> {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut rest = s.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = tail
+                .get(..2)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| MetadataError::new("Invalid percent-encoding in data URI"))?;
+            bytes.push(hex);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    Ok(bytes)
+}
+
+/// Decode standard or URL-safe base64. Whitespace is ignored, because
+/// data URIs in CSS are often wrapped, and padding is optional.
+fn base64_decode(data: &[u8]) -> Result<Vec<u8>, MetadataError> {
+    /// The value of a base64 digit
+    const fn digit(byte: u8) -> Option<u32> {
+        match byte {
+            b'A'..=b'Z' => Some((byte - b'A') as u32),
+            b'a'..=b'z' => Some((byte - b'a') as u32 + 26),
+            b'0'..=b'9' => Some((byte - b'0') as u32 + 52),
+            b'+' | b'-' => Some(62),
+            b'/' | b'_' => Some(63),
+            _ => None,
+        }
+    }
+
+    let invalid = || MetadataError::new("Invalid base64 data in data URI");
+    let digits = data
+        .iter()
+        .filter(|byte| !byte.is_ascii_whitespace())
+        .copied()
+        .collect::<Vec<u8>>();
+    let unpadded = digits
+        .strip_suffix(b"==")
+        .or_else(|| digits.strip_suffix(b"="))
+        .unwrap_or(&digits);
+    if unpadded.len() % 4 == 1 {
+        return Err(invalid());
+    }
+
+    let mut bytes = Vec::with_capacity(unpadded.len() / 4 * 3 + 2);
+    for chunk in unpadded.chunks(4) {
+        let mut buffer = 0;
+        for &byte in chunk {
+            buffer = buffer << 6 | digit(byte).ok_or_else(invalid)?;
+        }
+        // Align a partial chunk as if it was padded with zeros
+        buffer <<= 6 * (4 - chunk.len());
+        let decoded = buffer.to_be_bytes();
+        bytes.extend_from_slice(&decoded[1..chunk.len()]);
+    }
+    Ok(bytes)
+}
@@ -3,20 +3,78 @@ use std::error::Error;
 use std::fmt;
 use std::io::Error as IoError;
 use std::num::ParseFloatError;
+use std::string::FromUtf8Error;
+use std::sync::Arc;
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[non_exhaustive]
+/// The kind of an error, for callers that need to
+/// react to specific problems.
+pub enum ErrorKind {
+    /// The numbers of a viewBox have units (e.g. `0px 0px 96px 105px`),
+    /// but the spec only allows plain numbers.
+    ViewBoxHasUnits,
+    /// The input exceeds the maximum size passed by the caller
+    InputTooLarge,
+    /// The size of the image can't be determined, because neither
+    /// `width` and `height` nor a `viewBox` are usable
+    NoIntrinsicSize,
+    /// Elements are nested deeper than the limit passed by the caller
+    MaxDepthExceeded,
+    /// A unit is empty or consists only of whitespace
+    EmptyUnit,
+    /// Any other error
+    Other,
+}
+
+#[derive(Debug, Clone)]
+#[must_use]
 /// The error type of the library, which gets
 /// returned on parsing issues.
+///
+/// Errors caused by an underlying I/O, XML or number parsing error
+/// expose it through [`Error::source`].
 pub struct Metadata {
+    kind: ErrorKind,
     details: String,
+    source: Option<Arc<dyn Error + Send + Sync + 'static>>,
 }
 
 impl Metadata {
-    pub fn new(msg: &str) -> Metadata {
+    pub(crate) fn new(msg: &str) -> Metadata {
+        Metadata::with_kind(ErrorKind::Other, msg)
+    }
+
+    pub(crate) fn with_kind(kind: ErrorKind, msg: &str) -> Metadata {
         Metadata {
+            kind,
             details: msg.to_string(),
+            source: None,
         }
     }
+
+    /// Wrap an underlying error, which is returned by `source()`
+    pub(crate) fn wrap<E>(msg: &str, source: E) -> Metadata
+    where
+        E: Error + Send + Sync + 'static,
+    {
+        Metadata {
+            source: Some(Arc::new(source)),
+            ..Metadata::new(msg)
+        }
+    }
+
+    /// Keep the source of `other`, e.g. when adding context to an error
+    pub(crate) fn with_source_of(mut self, other: &Metadata) -> Metadata {
+        self.source.clone_from(&other.source);
+        self
+    }
+
+    /// Returns the kind of this error
+    #[must_use]
+    pub const fn kind(&self) -> ErrorKind {
+        self.kind
+    }
 }
 
 impl fmt::Display for Metadata {
@@ -26,25 +84,38 @@ impl fmt::Display for Metadata {
 }
 
 impl Error for Metadata {
-    fn description(&self) -> &str {
-        &self.details
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_deref().map(|e| e as &(dyn Error + 'static))
     }
 }
 
 impl From<ParseFloatError> for Metadata {
-    fn from(_: ParseFloatError) -> Metadata {
-        Metadata::new("Cannot convert string to float")
+    fn from(e: ParseFloatError) -> Metadata {
+        Metadata::wrap("Cannot convert string to float", e)
+    }
+}
+
+impl From<FromUtf8Error> for Metadata {
+    fn from(e: FromUtf8Error) -> Metadata {
+        Metadata::wrap(&e.to_string(), e)
     }
 }
 
 impl From<IoError> for Metadata {
     fn from(e: IoError) -> Metadata {
-        Metadata::new(&e.to_string())
+        Metadata::wrap(&e.to_string(), e)
+    }
+}
+
+#[cfg(feature = "http")]
+impl From<ureq::Error> for Metadata {
+    fn from(e: ureq::Error) -> Metadata {
+        Metadata::wrap(&e.to_string(), e)
     }
 }
 
 impl From<XMLError> for Metadata {
     fn from(e: XMLError) -> Metadata {
-        Metadata::new(&e.to_string())
+        Metadata::wrap(&e.to_string(), e)
     }
 }
@@ -0,0 +1,90 @@
+//! `arbitrary::Arbitrary` implementations for generating random metadata,
+//! e.g. for fuzzing code that consumes the types of this crate.
+use arbitrary::{Arbitrary, Result, Unstructured};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+use crate::{AttributeRanges, Dimension, Height, Metadata, Unit, Unitless, ViewBox, Width};
+
+/// Generate a float which is guaranteed to be finite
+fn finite_f64(u: &mut Unstructured<'_>) -> Result<f64> {
+    let value = f64::arbitrary(u)?;
+    Ok(if value.is_finite() { value } else { 0.0 })
+}
+
+impl<'a> Arbitrary<'a> for ViewBox {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(ViewBox {
+            min_x: finite_f64(u)?,
+            min_y: finite_f64(u)?,
+            width: finite_f64(u)?,
+            height: finite_f64(u)?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Unit {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.choose(Unit::all()).copied()
+    }
+}
+
+impl<'a> Arbitrary<'a> for Width {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Width {
+            width: finite_f64(u)?,
+            unit: u.arbitrary()?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Height {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Height {
+            height: finite_f64(u)?,
+            unit: u.arbitrary()?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Metadata {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let view_box: Option<ViewBox> = u.arbitrary()?;
+        let width: Option<Width> = u.arbitrary()?;
+        let height: Option<Height> = u.arbitrary()?;
+        Ok(Metadata {
+            view_box,
+            width,
+            height,
+            fill: u.arbitrary()?,
+            color: u.arbitrary()?,
+            overflow: u.arbitrary()?,
+            clip: u.arbitrary()?,
+            required_extensions: u.arbitrary()?,
+            required_features: u.arbitrary()?,
+            root_tag: "svg".to_string(),
+            width_dimension: width.map(|w| Dimension::from_length(w.width, w.unit)),
+            height_dimension: height.map(|h| Dimension::from_length(h.height, h.unit)),
+            unitless: Unitless::default(),
+            recovered: false,
+            attribute_ranges: AttributeRanges::default(),
+            defined_ids: Vec::new(),
+            font_size: None,
+            raw_view_box: view_box.map(|v| v.to_string()),
+            raw_width: width.map(|w| w.to_string()),
+            raw_height: height.map(|h| h.to_string()),
+            images: u.arbitrary()?,
+            use_refs: u.arbitrary()?,
+            has_raster: u.arbitrary()?,
+            declared_aspect_ratio: None,
+            namespaces: u.arbitrary()?,
+            attributes: u
+                .arbitrary::<BTreeMap<String, String>>()?
+                .into_iter()
+                .map(|(name, value)| (Cow::Owned(name), value))
+                .collect(),
+            classes: u.arbitrary()?,
+            base_url: None,
+        })
+    }
+}
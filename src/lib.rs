@@ -1,6 +1,6 @@
-//! `svg_metadata` is a Rust crate for parsing metadata information of SVG files.  
+//! `svg_metadata` is a Rust crate for parsing metadata information of SVG files.\
 //! In can be useful for getting information from SVG graphics without using
-//! a full-blown parser.  
+//! a full-blown parser.\
 //!
 //! As such, it has a very narrow scope and only provides access to the fields
 //! defined below.
@@ -16,30 +16,69 @@
     variant_size_differences,
     clippy::missing_const_for_fn
 )]
-#![deny(anonymous_parameters, macro_use_extern_crate, pointer_structural_match)]
+#![deny(anonymous_parameters, macro_use_extern_crate)]
 #![deny(missing_docs)]
+// `std::sync::LazyLock` would require Rust 1.80
+#![allow(clippy::non_std_lazy_statics)]
 
 #[cfg(doctest)]
 doctest!("../README.md");
 
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::convert::{AsRef, TryFrom};
+use std::fmt;
 use std::fs;
+use std::ops::Range;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 use once_cell::sync::Lazy;
 use regex::Regex;
 
+#[cfg(feature = "color")]
+mod color;
+mod decode;
 mod error;
-use crate::error::Metadata as MetadataError;
+#[cfg(feature = "arbitrary")]
+mod fuzz;
+mod scan;
+#[cfg(feature = "color")]
+pub use crate::color::Color;
+pub use crate::error::{ErrorKind, Metadata as MetadataError};
 
-/// Regex to split a list of elements in the viewBox
-static VBOX_ELEMENTS: Lazy<Regex> = Lazy::new(|| Regex::new(r",?\s+").unwrap());
+/// Regex to split a list of elements in the viewBox, which are separated
+/// by whitespace and/or a comma
+static VBOX_ELEMENTS: Lazy<Regex> = Lazy::new(|| Regex::new(r"\s*,\s*|\s+").unwrap());
+
+/// Regex to find the first four numbers of a malformed viewBox
+static VBOX_PREFIX: Lazy<Regex> = Lazy::new(|| {
+    let number = r"([\+-]?(?:\d+\.?\d*|\.\d+)(?:[eE][\+-]?\d+)?)";
+    let separator = r"(?:\s*,\s*|\s+)";
+    Regex::new(&format!(
+        r"^\s*{number}{separator}{number}{separator}{number}{separator}{number}"
+    ))
+    .unwrap()
+});
+
+/// The namespace of the deprecated `xlink:href` attribute
+const XLINK_NS: &str = "http://www.w3.org/1999/xlink";
+
+/// The maximum size of a response read by [`Metadata::parse_url`]
+#[cfg(feature = "http")]
+const MAX_RESPONSE_BYTES: usize = 10 * 1024 * 1024;
 
 /// Regex to extract dimension information (e.g. 100em)
-static DIMENSION: Lazy<Regex> = Lazy::new(|| Regex::new(r"([\+|-]?\d+\.?\d*)(\D\D?)?").unwrap());
+/// Whitespace between the number and the unit is captured separately,
+/// so that hand-edited values like `100 px` can still be read.
+static DIMENSION: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"([\+|-]?\d+\.?\d*)(\s*)(\D\D?)?").unwrap());
 
 #[derive(Debug, PartialEq, Copy, Clone)]
 /// Specifies the dimensions of an SVG image.
+///
+/// Prefer [`ViewBox::new`] over a struct literal to make sure
+/// all values are finite.
 pub struct ViewBox {
     /// The x coordinate of the left edge of the viewBox
     pub min_x: f64,
@@ -52,6 +91,7 @@ pub struct ViewBox {
 }
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[allow(deprecated)]
 /// Supported units for dimensions
 pub enum Unit {
     /// The default font size - usually the height of a character.
@@ -70,13 +110,23 @@ pub enum Unit {
     Mm,
     /// Inches
     In,
-    /// Percent
+    /// Percent, which refers to the viewBox.
+    ///
+    /// Percentages are not lengths, as they need a reference to be
+    /// resolved. Prefer matching on [`Dimension::Percentage`], which
+    /// keeps them apart. This unit is kept for `Width` and `Height`
+    /// for compatibility.
+    #[deprecated(note = "use Dimension::Percentage")]
     Percent,
 }
 
 impl TryFrom<&str> for Unit {
     type Error = MetadataError;
+    #[allow(deprecated)]
     fn try_from(s: &str) -> Result<Unit, MetadataError> {
+        if s.trim().is_empty() {
+            return Err(MetadataError::with_kind(ErrorKind::EmptyUnit, "Empty unit"));
+        }
         let unit = match s.to_lowercase().as_ref() {
             "em" => Unit::Em,
             "ex" => Unit::Ex,
@@ -93,8 +143,127 @@ impl TryFrom<&str> for Unit {
     }
 }
 
+impl fmt::Display for Unit {
+    #[allow(deprecated)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let unit = match self {
+            Unit::Em => "em",
+            Unit::Ex => "ex",
+            Unit::Px => "px",
+            Unit::Pt => "pt",
+            Unit::Pc => "pc",
+            Unit::Cm => "cm",
+            Unit::Mm => "mm",
+            Unit::In => "in",
+            Unit::Percent => "%",
+        };
+        f.write_str(unit)
+    }
+}
+
+/// Write a value with its unit, respecting the precision of the formatter
+fn fmt_dimension(value: f64, unit: Unit, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match f.precision() {
+        Some(precision) => write!(f, "{value:.precision$}{unit}"),
+        None => write!(f, "{value}{unit}"),
+    }
+}
+
+impl Unit {
+    /// Returns how many pixels one of this unit equals at the given DPI.
+    ///
+    /// Returns `None` for units which can't be converted without further
+    /// context, i.e. font-relative units and percentages.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Unit;
+    ///
+    /// assert_eq!(Unit::In.scale_factor(96.0), Some(96.0));
+    /// assert_eq!(Unit::Pt.scale_factor(96.0), Some(96.0 / 72.0));
+    /// assert_eq!(Unit::Em.scale_factor(96.0), None);
+    /// ```
+    #[must_use]
+    #[allow(deprecated)]
+    pub fn scale_factor(self, dpi: f64) -> Option<f64> {
+        match self {
+            Unit::Px => Some(1.0),
+            Unit::In => Some(dpi),
+            Unit::Cm => Some(dpi / 2.54),
+            Unit::Mm => Some(dpi / 25.4),
+            Unit::Pt => Some(dpi / 72.0),
+            Unit::Pc => Some(dpi / 6.0),
+            Unit::Em | Unit::Ex | Unit::Percent => None,
+        }
+    }
+
+    /// Returns the category of the unit, e.g. to group units in a picker.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::{Unit, UnitCategory};
+    ///
+    /// assert_eq!(Unit::Cm.category(), UnitCategory::Absolute);
+    /// assert_eq!(Unit::Em.category(), UnitCategory::FontRelative);
+    /// ```
+    #[must_use]
+    #[allow(deprecated)]
+    pub const fn category(self) -> UnitCategory {
+        match self {
+            Unit::Px | Unit::Pt | Unit::Pc | Unit::Cm | Unit::Mm | Unit::In => {
+                UnitCategory::Absolute
+            }
+            Unit::Em | Unit::Ex => UnitCategory::FontRelative,
+            Unit::Percent => UnitCategory::Viewport,
+        }
+    }
+
+    /// Returns all units, e.g. to populate a dropdown.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Unit;
+    ///
+    /// let names: Vec<String> = Unit::all().iter().map(ToString::to_string).collect();
+    /// assert!(names.contains(&"mm".to_string()));
+    /// ```
+    #[must_use]
+    #[allow(deprecated)]
+    pub const fn all() -> &'static [Unit] {
+        &[
+            Unit::Em,
+            Unit::Ex,
+            Unit::Px,
+            Unit::Pt,
+            Unit::Pc,
+            Unit::Cm,
+            Unit::Mm,
+            Unit::In,
+            Unit::Percent,
+        ]
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+#[non_exhaustive]
+/// The category of a [`Unit`], see [`Unit::category`]
+pub enum UnitCategory {
+    /// Units with a fixed size, like pixels or centimeters
+    Absolute,
+    /// Units relative to the font size, like `em`
+    FontRelative,
+    /// Units relative to the size of the viewport, i.e. percentages
+    Viewport,
+}
+
 #[derive(Debug, PartialEq, Copy, Clone)]
 /// Specifies the width of an SVG image.
+///
+/// Prefer [`Width::new`] over a struct literal to make sure
+/// the value is finite.
 pub struct Width {
     /// The width of the image
     pub width: f64,
@@ -102,7 +271,97 @@ pub struct Width {
     pub unit: Unit,
 }
 
+#[derive(Debug, PartialEq, Clone)]
+/// A `width` or `height` value as written in the SVG.
+pub enum Dimension {
+    /// A number with a unit, e.g. `10cm`. Percentages are represented by
+    /// [`Dimension::Percentage`] instead.
+    Length(f64, Unit),
+    /// A percentage of the viewBox, e.g. `50` for `50%`
+    Percentage(f64),
+    /// A CSS `calc()` expression such as `calc(100% - 20px)`.
+    /// The expression is not evaluated and kept as written.
+    Calc(String),
+}
+
+impl TryFrom<&str> for Dimension {
+    type Error = MetadataError;
+    fn try_from(s: &str) -> Result<Dimension, MetadataError> {
+        if is_calc(s) {
+            return Ok(Dimension::Calc(s.trim().to_string()));
+        }
+        let (value, unit) = parse_dimension(s)?;
+        Ok(Dimension::from_length(value, unit))
+    }
+}
+
+impl Dimension {
+    /// Create a dimension from a value with a unit, keeping percentages
+    /// apart from lengths
+    #[allow(deprecated)]
+    pub(crate) const fn from_length(value: f64, unit: Unit) -> Dimension {
+        match unit {
+            Unit::Percent => Dimension::Percentage(value),
+            _ => Dimension::Length(value, unit),
+        }
+    }
+
+    /// Resolve the dimension to pixels, where percentages refer to
+    /// `reference`, e.g. the width of the viewBox.
+    ///
+    /// Returns `None` for `calc()` expressions and for units which can't
+    /// be resolved with `options`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::{Dimension, ResolveOptions};
+    ///
+    /// let options = ResolveOptions::default();
+    /// let half = Dimension::try_from("50%").unwrap();
+    /// assert_eq!(half, Dimension::Percentage(50.0));
+    /// assert_eq!(half.resolve(200.0, &options), Some(100.0));
+    /// let inch = Dimension::try_from("1in").unwrap();
+    /// assert_eq!(inch.resolve(200.0, &options), Some(96.0));
+    /// ```
+    #[must_use]
+    pub fn resolve(&self, reference: f64, options: &ResolveOptions) -> Option<f64> {
+        match *self {
+            #[allow(deprecated)]
+            Dimension::Length(value, Unit::Percent) | Dimension::Percentage(value) => {
+                Some(value / 100.0 * reference)
+            }
+            Dimension::Length(value, unit) => options.length_px(value, unit),
+            Dimension::Calc(_) => None,
+        }
+    }
+}
+
+/// Check if a dimension is a `calc()` expression
+fn is_calc(s: &str) -> bool {
+    let s = s.trim();
+    s.get(..5)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("calc("))
+        && s.ends_with(')')
+}
+
+/// Make sure that a value is neither `NaN` nor infinite
+fn ensure_finite(name: &str, value: f64) -> Result<f64, MetadataError> {
+    if value.is_finite() {
+        Ok(value)
+    } else {
+        Err(MetadataError::new(&format!(
+            "Invalid {name}: Expected a finite number, got {value}"
+        )))
+    }
+}
+
 fn parse_dimension(s: &str) -> Result<(f64, Unit), MetadataError> {
+    if is_calc(s) {
+        return Err(MetadataError::new(&format!(
+            "Cannot convert calc() expression to a number: {s}"
+        )));
+    }
     let caps = DIMENSION
         .captures(s)
         .ok_or_else(|| MetadataError::new("Cannot read dimensions"))?;
@@ -111,11 +370,135 @@ fn parse_dimension(s: &str) -> Result<(f64, Unit), MetadataError> {
         .get(1)
         .ok_or_else(|| MetadataError::new("No width specified"))?
         .as_str();
-    let unit = caps.get(2).map_or("em", |m| m.as_str());
+    // A unit of only whitespace (e.g. in `100 `) is no unit at all
+    let unit = caps
+        .get(3)
+        .map(|m| m.as_str().trim())
+        .filter(|unit| !unit.is_empty())
+        .unwrap_or("em");
 
     Ok((val.parse::<f64>()?, Unit::try_from(unit)?))
 }
 
+/// Which of the `width` and `height` attributes are plain numbers without
+/// a unit. Such lengths are in user units, i.e. pixels.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+struct Unitless {
+    width: bool,
+    height: bool,
+}
+
+impl Unitless {
+    /// Check the `width` and `height` attributes of an element
+    fn from_element(elem: roxmltree::Node<'_, '_>) -> Unitless {
+        let unitless = |name| {
+            elem.attribute(name)
+                .is_some_and(|val: &str| val.trim().parse::<f64>().is_ok())
+        };
+        Unitless {
+            width: unitless("width"),
+            height: unitless("height"),
+        }
+    }
+}
+
+/// Check if a dimension has whitespace between the number and the unit,
+/// e.g. `100 px`. Those are accepted, except in strict mode.
+fn has_padded_unit(s: &str) -> bool {
+    DIMENSION
+        .captures(s)
+        .is_some_and(|caps| caps.get(2).is_some_and(|m| !m.is_empty()) && caps.get(3).is_some())
+}
+
+/// Parse a `font-size` attribute. Unlike `width` and `height`,
+/// a plain number is a length in user units (pixels).
+fn parse_font_size(s: &str) -> Option<(f64, Unit)> {
+    if let Ok(size) = s.trim().parse::<f64>() {
+        return Some((size, Unit::Px));
+    }
+    parse_dimension(s.trim()).ok()
+}
+
+impl Width {
+    /// Create a new `Width`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `width` is `NaN` or infinite.
+    pub fn new(width: f64, unit: Unit) -> Result<Width, MetadataError> {
+        Ok(Width {
+            width: ensure_finite("width", width)?,
+            unit,
+        })
+    }
+}
+
+/// Compare two lengths by their physical size, see [`Width::eq_physical`]
+fn eq_physical(a: f64, a_unit: Unit, b: f64, b_unit: Unit, dpi: f64) -> bool {
+    /// Maximum relative difference, to absorb rounding errors
+    const TOLERANCE: f64 = 1e-9;
+
+    let (a, b) = if a_unit == b_unit {
+        (a, b)
+    } else {
+        match (a_unit.scale_factor(dpi), b_unit.scale_factor(dpi)) {
+            (Some(a_factor), Some(b_factor)) => (a * a_factor, b * b_factor),
+            _ => return false,
+        }
+    };
+    (a - b).abs() <= TOLERANCE * a.abs().max(b.abs())
+}
+
+impl Width {
+    /// Returns `true` if both widths have the same physical size at the
+    /// given DPI, e.g. `1in` and `72pt`.
+    ///
+    /// Unlike `==`, this compares values in different units and allows for
+    /// rounding errors. Widths in different units of which one is
+    /// font-relative or a percentage are never equal, as they can't be
+    /// converted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Width;
+    ///
+    /// let inch = Width::try_from("1in").unwrap();
+    /// let points = Width::try_from("72pt").unwrap();
+    /// assert_ne!(inch, points);
+    /// assert!(inch.eq_physical(&points, 96.0));
+    /// ```
+    #[must_use]
+    pub fn eq_physical(&self, other: &Width, dpi: f64) -> bool {
+        eq_physical(self.width, self.unit, other.width, other.unit, dpi)
+    }
+
+    /// Format the width with a fixed number of decimals,
+    /// e.g. `10.00cm` instead of `10cm`.
+    ///
+    /// This is the same as formatting with `{:.decimals$}`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::{Unit, Width};
+    ///
+    /// let width = Width::new(10.0, Unit::Cm).unwrap();
+    /// assert_eq!(width.to_string(), "10cm");
+    /// assert_eq!(width.format_with_precision(2), "10.00cm");
+    /// ```
+    #[must_use]
+    pub fn format_with_precision(&self, decimals: usize) -> String {
+        format!("{self:.decimals$}")
+    }
+}
+
+impl fmt::Display for Width {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_dimension(self.width, self.unit, f)
+    }
+}
+
 impl TryFrom<&str> for Width {
     type Error = MetadataError;
     fn try_from(s: &str) -> Result<Width, MetadataError> {
@@ -126,6 +509,9 @@ impl TryFrom<&str> for Width {
 
 #[derive(Debug, PartialEq, Copy, Clone)]
 /// Specifies the height of an SVG image.
+///
+/// Prefer [`Height::new`] over a struct literal to make sure
+/// the value is finite.
 pub struct Height {
     /// The height of the image
     pub height: f64,
@@ -133,6 +519,44 @@ pub struct Height {
     pub unit: Unit,
 }
 
+impl Height {
+    /// Create a new `Height`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `height` is `NaN` or infinite.
+    pub fn new(height: f64, unit: Unit) -> Result<Height, MetadataError> {
+        Ok(Height {
+            height: ensure_finite("height", height)?,
+            unit,
+        })
+    }
+}
+
+impl Height {
+    /// Returns `true` if both heights have the same physical size at the
+    /// given DPI, see [`Width::eq_physical`].
+    #[must_use]
+    pub fn eq_physical(&self, other: &Height, dpi: f64) -> bool {
+        eq_physical(self.height, self.unit, other.height, other.unit, dpi)
+    }
+
+    /// Format the height with a fixed number of decimals,
+    /// e.g. `10.00cm` instead of `10cm`.
+    ///
+    /// This is the same as formatting with `{:.decimals$}`.
+    #[must_use]
+    pub fn format_with_precision(&self, decimals: usize) -> String {
+        format!("{self:.decimals$}")
+    }
+}
+
+impl fmt::Display for Height {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_dimension(self.height, self.unit, f)
+    }
+}
+
 impl TryFrom<&str> for Height {
     type Error = MetadataError;
     fn try_from(s: &str) -> Result<Height, MetadataError> {
@@ -141,10 +565,117 @@ impl TryFrom<&str> for Height {
     }
 }
 
+/// Flip the sign, e.g. for transforms which mirror an axis.
+/// The unit is kept.
+///
+/// # Example
+///
+/// ```rust
+/// use svg_metadata::{Unit, Width};
+///
+/// let width = Width::new(10.0, Unit::Px).unwrap();
+/// assert_eq!(-width, Width::new(-10.0, Unit::Px).unwrap());
+/// ```
+impl std::ops::Neg for Width {
+    type Output = Width;
+    fn neg(self) -> Width {
+        Width {
+            width: -self.width,
+            ..self
+        }
+    }
+}
+
+/// Flip the sign, e.g. for transforms which mirror an axis.
+/// The unit is kept.
+impl std::ops::Neg for Height {
+    type Output = Height;
+    fn neg(self) -> Height {
+        Height {
+            height: -self.height,
+            ..self
+        }
+    }
+}
+
+/// Swap axes, e.g. when rotating an image by 90°.
+///
+/// # Example
+///
+/// ```rust
+/// use svg_metadata::{Height, Unit, Width};
+///
+/// let width = Width::new(10.0, Unit::Cm).unwrap();
+/// let height = Height::from(width);
+/// assert_eq!(height, Height::new(10.0, Unit::Cm).unwrap());
+/// ```
+impl From<Width> for Height {
+    fn from(width: Width) -> Height {
+        Height {
+            height: width.width,
+            unit: width.unit,
+        }
+    }
+}
+
+/// Swap axes, e.g. when rotating an image by 90°.
+impl From<Height> for Width {
+    fn from(height: Height) -> Width {
+        Width {
+            width: height.height,
+            unit: height.unit,
+        }
+    }
+}
+
+impl fmt::Display for ViewBox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.min_x, self.min_y, self.width, self.height
+        )
+    }
+}
+
+/// Compare with a viewBox as written in an attribute, see
+/// [`ViewBox::matches_str`].
+///
+/// # Example
+///
+/// ```rust
+/// use svg_metadata::ViewBox;
+///
+/// let view_box = ViewBox::new(0.0, 0.0, 96.0, 105.0).unwrap();
+/// assert_eq!(view_box, ViewBox::try_from("0 0 96 105").unwrap());
+/// assert_eq!(view_box, "0 0 96 105");
+/// assert_eq!(view_box, "0, 0, 96, 105.0");
+/// assert_ne!(view_box, "0 0 96");
+/// ```
+impl PartialEq<&str> for ViewBox {
+    fn eq(&self, other: &&str) -> bool {
+        self.matches_str(other)
+    }
+}
+
+/// Remove a unit from a number like `10px`.
+/// Anything else is returned unchanged.
+fn strip_unit(s: &str) -> &str {
+    let number = s.trim_end_matches(|c: char| c.is_ascii_alphabetic() || c == '%');
+    let unit = &s[number.len()..];
+    if !unit.is_empty() && number.parse::<f64>().is_ok() && Unit::try_from(unit).is_ok() {
+        number
+    } else {
+        s
+    }
+}
+
 impl TryFrom<&str> for ViewBox {
     type Error = MetadataError;
     fn try_from(s: &str) -> Result<ViewBox, MetadataError> {
-        let elem: Vec<&str> = VBOX_ELEMENTS.split(s).collect();
+        // Surrounding whitespace (including line breaks) would otherwise
+        // result in empty elements
+        let elem: Vec<&str> = VBOX_ELEMENTS.split(s.trim()).collect();
 
         if elem.len() != 4 {
             return Err(MetadataError::new(&format!(
@@ -152,6 +683,12 @@ impl TryFrom<&str> for ViewBox {
                 elem.len()
             )));
         }
+        if elem.iter().any(|e| strip_unit(e) != *e) {
+            return Err(MetadataError::with_kind(
+                ErrorKind::ViewBoxHasUnits,
+                &format!("Invalid view_box: Numbers must not have units, got {s}"),
+            ));
+        }
         let min_x = elem[0].parse::<f64>()?;
         let min_y = elem[1].parse::<f64>()?;
         let width = elem[2].parse::<f64>()?;
@@ -166,285 +703,5942 @@ impl TryFrom<&str> for ViewBox {
     }
 }
 
-#[derive(Debug, PartialEq, Copy, Clone)]
-/// Contains all metadata that was
-/// extracted from an SVG image.
-pub struct Metadata {
-    /// The viewBox of the SVG image
-    /// A viewBox is a rectangle that defines the dimensions of the image.
-    /// For more information see: <https://developer.mozilla.org/en-US/docs/Web/SVG/Attribute/viewBox>
-    pub view_box: Option<ViewBox>,
-    /// The width of the SVG image
-    pub width: Option<Width>,
-    /// The height of the SVG image
-    pub height: Option<Height>,
+/// Implement `TryFrom<String>` and `TryFrom<&String>` by delegating to
+/// the `&str` implementation, so owned strings can be used directly
+macro_rules! impl_try_from_string {
+    ($ty:ident, $example:literal) => {
+        #[doc = concat!(
+                    "Parse an owned string, like `", stringify!($ty), "::try_from(&str)`.\n\n",
+                    "# Example\n\n",
+                    "```rust\n",
+                    "use svg_metadata::", stringify!($ty), ";\n\n",
+                    "let s = String::from(\"", $example, "\");\n",
+                    "let borrowed = ", stringify!($ty), "::try_from(&s).unwrap();\n",
+                    "assert_eq!(", stringify!($ty), "::try_from(s).unwrap(), borrowed);\n",
+                    "```",
+                )]
+        impl TryFrom<String> for $ty {
+            type Error = MetadataError;
+            fn try_from(s: String) -> Result<$ty, MetadataError> {
+                $ty::try_from(s.as_str())
+            }
+        }
+
+        #[doc = concat!("Parse a borrowed `String`, like `", stringify!($ty), "::try_from(&str)`.")]
+        impl TryFrom<&String> for $ty {
+            type Error = MetadataError;
+            fn try_from(s: &String) -> Result<$ty, MetadataError> {
+                $ty::try_from(s.as_str())
+            }
+        }
+    };
 }
 
-impl Metadata {
-    /// Parse an SVG file and extract metadata from it.
+impl_try_from_string!(Unit, "px");
+impl_try_from_string!(Dimension, "calc(100% - 1em)");
+impl_try_from_string!(Width, "10px");
+impl_try_from_string!(Height, "2.5cm");
+impl_try_from_string!(ViewBox, "0 0 96 105");
+
+impl ViewBox {
+    /// Read a malformed viewBox by removing units from its numbers
+    /// or by ignoring anything that follows the first four numbers.
+    fn recover(s: &str) -> Option<ViewBox> {
+        let elem: Vec<&str> = VBOX_ELEMENTS.split(s).map(strip_unit).collect();
+        if let Ok(view_box) = ViewBox::try_from(elem.join(" ").as_str()) {
+            return Some(view_box);
+        }
+
+        let caps = VBOX_PREFIX.captures(s)?;
+        let number = |i: usize| caps.get(i)?.as_str().parse::<f64>().ok();
+        Some(ViewBox {
+            min_x: number(1)?,
+            min_y: number(2)?,
+            width: number(3)?,
+            height: number(4)?,
+        })
+    }
+
+    /// Read a viewBox which uses commas as decimal separators,
+    /// e.g. `0 0 96,5 105,2`. See [`ParseOptions::parse_euro_decimals`].
+    fn from_euro_decimals(s: &str) -> Option<ViewBox> {
+        match euro_decimal_values(s)?[..] {
+            [min_x, min_y, width, height] => Some(ViewBox {
+                min_x,
+                min_y,
+                width,
+                height,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Create a new `ViewBox`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the values is `NaN` or infinite.
+    pub fn new(min_x: f64, min_y: f64, width: f64, height: f64) -> Result<ViewBox, MetadataError> {
+        Ok(ViewBox {
+            min_x: ensure_finite("min_x", min_x)?,
+            min_y: ensure_finite("min_y", min_y)?,
+            width: ensure_finite("width", width)?,
+            height: ensure_finite("height", height)?,
+        })
+    }
+
+    /// Create a `ViewBox` with its origin at `0 0`.
+    ///
+    /// Unlike [`ViewBox::new`] this doesn't validate the values.
     ///
     /// # Example
     ///
     /// ```rust
-    /// use svg_metadata::{Metadata, ViewBox};
+    /// use svg_metadata::ViewBox;
     ///
-    /// let meta = Metadata::parse_file("fixtures/test.svg").unwrap();
-    ///     assert_eq!(
-    ///     meta.view_box,
-    ///     Some(ViewBox {
-    ///         min_x: 0.0,
-    ///         min_y: 0.0,
-    ///         width: 96.0,
-    ///         height: 105.0
-    ///     })
-    /// );
+    /// let view_box = ViewBox::from_dimensions(96.0, 105.0);
+    /// assert_eq!(view_box, ViewBox::try_from("0 0 96 105").unwrap());
     /// ```
+    #[must_use]
+    pub const fn from_dimensions(width: f64, height: f64) -> ViewBox {
+        ViewBox {
+            min_x: 0.0,
+            min_y: 0.0,
+            width,
+            height,
+        }
+    }
+
+    /// Round all values to the nearest integer.
     ///
-    /// # Errors
+    /// Half-way values are rounded away from zero, so `-0.5` becomes `-1`
+    /// and `0.5` becomes `1` (see [`f64::round`]).
     ///
-    /// Returns an error if the file cannot be read or if the SVG data is invalid.
-    pub fn parse_file<T: Into<PathBuf>>(path: T) -> Result<Metadata, MetadataError> {
-        let input = fs::read_to_string(path.into())?;
-        Self::parse(input)
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::ViewBox;
+    ///
+    /// let view_box = ViewBox::try_from("0.4 0.6 96.5 105.4").unwrap();
+    /// assert_eq!(view_box.round(), ViewBox::try_from("0 1 97 105").unwrap());
+    /// ```
+    #[must_use]
+    pub fn round(&self) -> ViewBox {
+        self.map(f64::round)
     }
 
-    /// Parse SVG data and extract metadata from it.
+    /// Round all values down to the next integer.
+    ///
+    /// Negative values are rounded towards negative infinity,
+    /// so `-0.5` becomes `-1`.
+    #[must_use]
+    pub fn floor(&self) -> ViewBox {
+        self.map(f64::floor)
+    }
+
+    /// Round all values up to the next integer.
+    ///
+    /// Negative values are rounded towards positive infinity,
+    /// so `-0.5` becomes `-0`.
+    #[must_use]
+    pub fn ceil(&self) -> ViewBox {
+        self.map(f64::ceil)
+    }
+
+    /// Returns the smallest integer rectangle covering the viewBox, as
+    /// `(x, y, width, height)`, e.g. for blitting into a framebuffer.
+    ///
+    /// The box is normalized first (see [`ViewBox::normalized`]). The
+    /// origin is rounded down and the far edges are rounded up, so no
+    /// part of the box is left uncovered. Unlike [`ViewBox::round`], the
+    /// extent may therefore grow by up to two pixels. Values outside of
+    /// the range of `i64` saturate and NaN becomes zero.
     ///
     /// # Example
     ///
     /// ```rust
-    /// use svg_metadata::{Metadata, ViewBox, Width, Height, Unit};
+    /// use svg_metadata::ViewBox;
     ///
-    /// let svg = r#"<svg viewBox="0 1 99 100" width="2em" height="10cm" xmlns="http://www.w3.org/2000/svg">
-    ///  <rect x="0" y="0" width="100%" height="100%"/>
-    /// </svg>"#;
+    /// let view_box = ViewBox::try_from("0.5 0.5 10 10").unwrap();
+    /// assert_eq!(view_box.to_int_rect(), (0, 0, 11, 11));
+    /// ```
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn to_int_rect(&self) -> (i64, i64, i64, i64) {
+        let view_box = self.normalized();
+        let x = view_box.min_x.floor() as i64;
+        let y = view_box.min_y.floor() as i64;
+        let right = (view_box.min_x + view_box.width).ceil() as i64;
+        let bottom = (view_box.min_y + view_box.height).ceil() as i64;
+        (x, y, right.saturating_sub(x), bottom.saturating_sub(y))
+    }
+
+    /// Apply a function to all values
+    fn map(&self, f: impl Fn(f64) -> f64) -> ViewBox {
+        ViewBox {
+            min_x: f(self.min_x),
+            min_y: f(self.min_y),
+            width: f(self.width),
+            height: f(self.height),
+        }
+    }
+
+    /// Returns the values as `[min_x, min_y, width, height]` in single
+    /// precision, e.g. for uploading them to a GPU.
     ///
-    /// let meta = Metadata::parse(svg).unwrap();
-    /// assert_eq!(
-    ///    meta.view_box,
-    ///    Some(ViewBox {
-    ///      min_x: 0.0,
-    ///      min_y: 1.0,
-    ///      width: 99.0,
-    ///      height: 100.0
-    ///    })
+    /// The conversion is lossy: values are rounded to the nearest `f32`
+    /// and values outside of its range become infinite.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub const fn to_f32_array(&self) -> [f32; 4] {
+        [
+            self.min_x as f32,
+            self.min_y as f32,
+            self.width as f32,
+            self.height as f32,
+        ]
+    }
+
+    /// Returns `true` if both viewBoxes have the same size,
+    /// regardless of their origin.
+    ///
+    /// Negative extents are compared by their absolute value.
+    /// Like `==`, this compares the values exactly.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::ViewBox;
+    ///
+    /// let a = ViewBox::try_from("-8 -8 16 16").unwrap();
+    /// let b = ViewBox::try_from("0 0 16 16").unwrap();
+    /// assert_ne!(a, b);
+    /// assert!(a.same_size(&b));
+    /// ```
+    #[must_use]
+    #[allow(clippy::float_cmp)]
+    pub fn same_size(&self, other: &ViewBox) -> bool {
+        self.width.abs() == other.width.abs() && self.height.abs() == other.height.abs()
+    }
+
+    /// Returns the factor to scale the viewBox by, so that it fits into
+    /// a target of the given size while keeping its aspect ratio.
+    ///
+    /// With `slice == false` (`meet` in `preserveAspectRatio`) the whole
+    /// viewBox is visible, so the smaller of the per-axis ratios is used.
+    /// With `slice == true` the target is covered completely, so the larger
+    /// one is used. Negative extents are treated by their absolute value.
+    /// An empty viewBox results in an infinite or `NaN` factor.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::ViewBox;
+    ///
+    /// let view_box = ViewBox::try_from("0 0 100 50").unwrap();
+    /// assert_eq!(view_box.fit_scale(400.0, 400.0, false), 4.0);
+    /// assert_eq!(view_box.fit_scale(400.0, 400.0, true), 8.0);
+    /// ```
+    #[must_use]
+    pub fn fit_scale(&self, target_w: f64, target_h: f64, slice: bool) -> f64 {
+        let scale_x = target_w / self.width.abs();
+        let scale_y = target_h / self.height.abs();
+        if slice {
+            scale_x.max(scale_y)
+        } else {
+            scale_x.min(scale_y)
+        }
+    }
+
+    /// Returns the affine transform which maps the viewBox onto the
+    /// `target` rectangle, as `[a, b, c, d, e, f]` like in the SVG
+    /// `matrix()` transform: a point `(x, y)` is mapped to
+    /// `(a * x + c * y + e, b * x + d * y + f)`.
+    ///
+    /// The viewBox is fitted into the target according to `preserve`,
+    /// where `None` is the default `xMidYMid meet`. An empty viewBox
+    /// results in infinite or `NaN` values.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::{PreserveAspectRatio, ViewBox};
+    ///
+    /// let view_box = ViewBox::try_from("0 0 10 10").unwrap();
+    /// let target = ViewBox::try_from("0 0 200 100").unwrap();
+    /// // Scaled by 10 and centered horizontally
+    /// assert_eq!(
+    ///     view_box.transform_to(&target, None),
+    ///     [10.0, 0.0, 0.0, 10.0, 50.0, 0.0]
     /// );
+    ///
+    /// let stretch = PreserveAspectRatio::try_from("none").unwrap();
     /// assert_eq!(
-    ///   meta.width,
-    ///   Some(Width {
-    ///     width: 2.0,
-    ///     unit: Unit::Em
-    ///   })
+    ///     view_box.transform_to(&target, Some(stretch)),
+    ///     [20.0, 0.0, 0.0, 10.0, 0.0, 0.0]
     /// );
+    /// ```
+    #[must_use]
+    pub fn transform_to(
+        &self,
+        target: &ViewBox,
+        preserve: Option<PreserveAspectRatio>,
+    ) -> [f64; 6] {
+        let preserve = preserve.unwrap_or_default();
+        let (scale_x, scale_y) = if preserve.align == Align::None {
+            (target.width / self.width, target.height / self.height)
+        } else {
+            let scale = self.fit_scale(target.width, target.height, preserve.slice);
+            (scale, scale)
+        };
+        // Distribute the space which isn't covered by the scaled viewBox
+        let (align_x, align_y) = preserve.align.factors();
+        let translate_x =
+            target.min_x - self.min_x * scale_x + (target.width - self.width * scale_x) * align_x;
+        let translate_y =
+            target.min_y - self.min_y * scale_y + (target.height - self.height * scale_y) * align_y;
+        [scale_x, 0.0, 0.0, scale_y, translate_x, translate_y]
+    }
+
+    /// Returns the point closest to `(x, y)` within the viewBox, e.g. to
+    /// keep interaction coordinates inside the drawing area.
+    ///
+    /// Negative extents are handled by normalizing the viewBox first.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::ViewBox;
+    ///
+    /// let view_box = ViewBox::try_from("0 0 100 50").unwrap();
+    /// assert_eq!(view_box.clamp_point(120.0, 20.0), (100.0, 20.0));
+    /// ```
+    #[must_use]
+    pub fn clamp_point(&self, x: f64, y: f64) -> (f64, f64) {
+        let view_box = self.normalized();
+        // Unlike `f64::clamp`, this doesn't panic on NaN bounds
+        let clamp = |value: f64, min: f64, extent: f64| value.max(min).min(min + extent);
+        (
+            clamp(x, view_box.min_x, view_box.width),
+            clamp(y, view_box.min_y, view_box.height),
+        )
+    }
+
+    /// Shrinks the viewBox by `margin` on each side.
+    ///
+    /// The origin moves inward and the extents shrink by twice the margin.
+    /// A negative margin grows the box (see [`ViewBox::expand`]). If the
+    /// margin exceeds half of an extent, that extent becomes zero and the
+    /// box collapses onto its center, so extents never turn negative.
+    /// The result is normalized (see [`ViewBox::normalized`]).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::ViewBox;
+    ///
+    /// let view_box = ViewBox::try_from("0 0 100 100").unwrap();
+    /// assert_eq!(view_box.inset(10.0), ViewBox::try_from("10 10 80 80").unwrap());
+    /// ```
+    #[must_use]
+    pub fn inset(&self, margin: f64) -> ViewBox {
+        let view_box = self.normalized();
+        let margin_x = margin.min(view_box.width / 2.0);
+        let margin_y = margin.min(view_box.height / 2.0);
+        ViewBox {
+            min_x: view_box.min_x + margin_x,
+            min_y: view_box.min_y + margin_y,
+            width: view_box.width - 2.0 * margin_x,
+            height: view_box.height - 2.0 * margin_y,
+        }
+    }
+
+    /// Grows the viewBox by `margin` on each side, e.g. to add padding.
+    /// This is the same as `inset(-margin)`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::ViewBox;
+    ///
+    /// let view_box = ViewBox::try_from("0 0 100 100").unwrap();
+    /// assert_eq!(view_box.expand(10.0), ViewBox::try_from("-10 -10 120 120").unwrap());
+    /// ```
+    #[must_use]
+    pub fn expand(&self, margin: f64) -> ViewBox {
+        self.inset(-margin)
+    }
+
+    /// Grows the shorter side of the viewBox until `width / height` equals
+    /// `target_ratio`, keeping the box centered. Nothing is cropped, so
+    /// this letterboxes the content when rendering into a frame with a
+    /// fixed aspect ratio.
+    ///
+    /// The result is normalized (see [`ViewBox::normalized`]). If the
+    /// target ratio isn't a positive, finite number or the box has a zero
+    /// extent, the normalized box is returned unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::ViewBox;
+    ///
+    /// let view_box = ViewBox::try_from("0 0 100 100").unwrap();
     /// assert_eq!(
-    ///  meta.height,
-    ///  Some(Height {
-    ///    height: 10.0,
-    ///    unit: Unit::Cm
-    ///   })
+    ///     view_box.pad_to_aspect(2.0),
+    ///     ViewBox::try_from("-50 0 200 100").unwrap()
     /// );
     /// ```
+    #[must_use]
+    pub fn pad_to_aspect(&self, target_ratio: f64) -> ViewBox {
+        let view_box = self.normalized();
+        if !(target_ratio.is_finite() && target_ratio > 0.0)
+            || view_box.width == 0.0
+            || view_box.height == 0.0
+        {
+            return view_box;
+        }
+        let width = view_box.width.max(view_box.height * target_ratio);
+        let height = view_box.height.max(view_box.width / target_ratio);
+        ViewBox {
+            min_x: view_box.min_x - (width - view_box.width) / 2.0,
+            min_y: view_box.min_y - (height - view_box.height) / 2.0,
+            width,
+            height,
+        }
+    }
+
+    /// Linearly interpolates between this viewBox and `other`, e.g. to
+    /// animate zooming and panning. `t = 0` returns this viewBox and
+    /// `t = 1` returns `other`.
     ///
-    /// # Errors
+    /// `t` is not clamped, so values outside of `[0, 1]` extrapolate,
+    /// which is useful for easing functions that overshoot.
     ///
-    /// Returns an error if the SVG data is invalid.
-    pub fn parse<T: AsRef<str>>(input: T) -> Result<Metadata, MetadataError> {
-        let doc = roxmltree::Document::parse_with_options(
-            input.as_ref(),
-            roxmltree::ParsingOptions {
-                // Allow DTDs (e.g. `<!DOCTYPE svg PUBLIC "-//W3C//DTD SVG 1.1//EN"`)
-                // See [`roxmltree` docs](https://docs.rs/roxmltree/latest/roxmltree/struct.ParsingOptions.html#structfield.allow_dtd)
-                // for more info
-                allow_dtd: true,
-                ..Default::default()
-            },
-        )?;
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::ViewBox;
+    ///
+    /// let from = ViewBox::try_from("0 0 100 100").unwrap();
+    /// let to = ViewBox::try_from("50 50 10 10").unwrap();
+    /// assert_eq!(from.lerp(&to, 0.5), ViewBox::try_from("25 25 55 55").unwrap());
+    /// ```
+    #[must_use]
+    pub fn lerp(&self, other: &ViewBox, t: f64) -> ViewBox {
+        // Exact at both ends, unlike `a + (b - a) * t`
+        let lerp = |a: f64, b: f64| a * (1.0 - t) + b * t;
+        ViewBox {
+            min_x: lerp(self.min_x, other.min_x),
+            min_y: lerp(self.min_y, other.min_y),
+            width: lerp(self.width, other.width),
+            height: lerp(self.height, other.height),
+        }
+    }
+
+    /// Returns `true` if `s` parses to an equal viewBox, so differences in
+    /// formatting (e.g. `96` and `96.0`, or commas) don't matter. Returns
+    /// `false` if `s` can't be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::ViewBox;
+    ///
+    /// let view_box = ViewBox::try_from("0 0 96 105").unwrap();
+    /// assert!(view_box.matches_str("0, 0, 96.0, 105"));
+    /// assert!(!view_box.matches_str("0 0 96px 105px"));
+    /// ```
+    #[must_use]
+    pub fn matches_str(&self, s: &str) -> bool {
+        ViewBox::try_from(s).is_ok_and(|other| *self == other)
+    }
+
+    /// Returns the outline of the viewBox as path data, for use in the
+    /// `d` attribute of a `<path>`, e.g. to draw it as a debug overlay.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::ViewBox;
+    ///
+    /// let view_box = ViewBox::try_from("-5 0 10 20").unwrap();
+    /// assert_eq!(view_box.to_rect_path(), "M -5,0 h 10 v 20 h -10 Z");
+    /// ```
+    #[must_use]
+    pub fn to_rect_path(&self) -> String {
+        format!(
+            "M {},{} h {} v {} h {} Z",
+            self.min_x, self.min_y, self.width, self.height, -self.width
+        )
+    }
+
+    /// Maps a point in viewBox coordinates to pixel coordinates of the
+    /// image rendered at `pixel_w` × `pixel_h`.
+    ///
+    /// Each axis is scaled independently, like with
+    /// `preserveAspectRatio="none"`. For other values of
+    /// `preserveAspectRatio`, the image is only mapped correctly if the
+    /// pixel size has the same aspect ratio as the viewBox.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::ViewBox;
+    ///
+    /// let view_box = ViewBox::try_from("-50 -50 100 100").unwrap();
+    /// assert_eq!(view_box.to_pixel(0.0, 0.0, 200.0, 200.0), (100.0, 100.0));
+    /// assert_eq!(view_box.from_pixel(100.0, 100.0, 200.0, 200.0), (0.0, 0.0));
+    /// ```
+    #[must_use]
+    pub fn to_pixel(&self, vb_x: f64, vb_y: f64, pixel_w: f64, pixel_h: f64) -> (f64, f64) {
+        (
+            (vb_x - self.min_x) * pixel_w / self.width,
+            (vb_y - self.min_y) * pixel_h / self.height,
+        )
+    }
+
+    /// Maps a point in pixel coordinates of the image rendered at
+    /// `pixel_w` × `pixel_h` back to viewBox coordinates, e.g. for
+    /// hit-testing. This is the inverse of [`ViewBox::to_pixel`].
+    #[must_use]
+    #[allow(clippy::wrong_self_convention)]
+    pub fn from_pixel(&self, x: f64, y: f64, pixel_w: f64, pixel_h: f64) -> (f64, f64) {
+        (
+            self.min_x + x * self.width / pixel_w,
+            self.min_y + y * self.height / pixel_h,
+        )
+    }
+
+    /// Splits the viewBox into a grid of `cols` × `rows` equally sized
+    /// viewBoxes, e.g. to cut a sprite sheet into tiles.
+    ///
+    /// The tiles are returned in row-major order. If `cols` or `rows`
+    /// is zero, the result is empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::ViewBox;
+    ///
+    /// let view_box = ViewBox::try_from("0 0 100 50").unwrap();
+    /// let tiles = view_box.split_into_grid(2, 1);
+    /// assert_eq!(tiles[1], ViewBox::try_from("50 0 50 50").unwrap());
+    /// ```
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn split_into_grid(&self, cols: usize, rows: usize) -> Vec<ViewBox> {
+        // Compute the edges from the fractions, so that the last tile ends
+        // exactly at the edge of the viewBox
+        let x = |col: usize| self.min_x + self.width * col as f64 / cols as f64;
+        let y = |row: usize| self.min_y + self.height * row as f64 / rows as f64;
+        (0..rows)
+            .flat_map(|row| (0..cols).map(move |col| (col, row)))
+            .map(|(col, row)| ViewBox {
+                min_x: x(col),
+                min_y: y(row),
+                width: x(col + 1) - x(col),
+                height: y(row + 1) - y(row),
+            })
+            .collect()
+    }
+
+    /// Returns an evenly spaced lattice of `cols` × `rows` points inside
+    /// the viewBox, e.g. for calibration overlays.
+    ///
+    /// The box is normalized first (see [`ViewBox::normalized`]). The
+    /// outer points lie on the edges of the box, so with two or more
+    /// columns the first and last points are at the left and right edges.
+    /// A single column or row is placed at the center instead. The points
+    /// are returned in row-major order. If `cols` or `rows` is zero, the
+    /// result is empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::ViewBox;
+    ///
+    /// let view_box = ViewBox::try_from("0 0 100 50").unwrap();
+    /// assert_eq!(view_box.grid_points(2, 1), [(0.0, 25.0), (100.0, 25.0)]);
+    /// ```
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn grid_points(&self, cols: usize, rows: usize) -> Vec<(f64, f64)> {
+        /// The position of point `index` of `count` along an axis
+        fn position(min: f64, extent: f64, index: usize, count: usize) -> f64 {
+            if count == 1 {
+                min + extent / 2.0
+            } else {
+                min + extent * index as f64 / (count - 1) as f64
+            }
+        }
+
+        let view_box = self.normalized();
+        (0..rows)
+            .flat_map(|row| (0..cols).map(move |col| (col, row)))
+            .map(|(col, row)| {
+                (
+                    position(view_box.min_x, view_box.width, col, cols),
+                    position(view_box.min_y, view_box.height, row, rows),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns the length of the diagonal of the viewBox, e.g. to scale
+    /// stroke widths independently of the aspect ratio.
+    /// Negative extents count by their absolute value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::ViewBox;
+    ///
+    /// let view_box = ViewBox::try_from("0 0 3 4").unwrap();
+    /// assert_eq!(view_box.diagonal(), 5.0);
+    /// ```
+    #[must_use]
+    pub fn diagonal(&self) -> f64 {
+        self.width.hypot(self.height)
+    }
+
+    /// Returns the overlapping area of two viewBoxes.
+    ///
+    /// Both boxes are normalized first, so negative extents are treated as
+    /// boxes extending to the left or upwards.
+    /// Boxes which only touch along an edge or at a corner have no common
+    /// area and return `None`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::ViewBox;
+    ///
+    /// let a = ViewBox::try_from("0 0 10 10").unwrap();
+    /// let b = ViewBox::try_from("5 5 10 10").unwrap();
+    /// assert_eq!(a.intersection(&b), Some(ViewBox::try_from("5 5 5 5").unwrap()));
+    /// ```
+    #[must_use]
+    pub fn intersection(&self, other: &ViewBox) -> Option<ViewBox> {
+        let a = self.normalized();
+        let b = other.normalized();
+
+        let min_x = a.min_x.max(b.min_x);
+        let min_y = a.min_y.max(b.min_y);
+        let max_x = (a.min_x + a.width).min(b.min_x + b.width);
+        let max_y = (a.min_y + a.height).min(b.min_y + b.height);
+
+        if max_x <= min_x || max_y <= min_y {
+            return None;
+        }
+        Some(ViewBox {
+            min_x,
+            min_y,
+            width: max_x - min_x,
+            height: max_y - min_y,
+        })
+    }
+
+    /// Returns a viewBox covering the same area with a non-negative
+    /// width and height.
+    ///
+    /// A negative width means the box extends to the left of `min_x`,
+    /// so the origin is moved to the other edge and the width is flipped.
+    /// The same applies to a negative height.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::ViewBox;
+    ///
+    /// let view_box = ViewBox::try_from("10 0 -10 5").unwrap();
+    /// assert_eq!(view_box.normalized(), ViewBox::try_from("0 0 10 5").unwrap());
+    /// ```
+    #[must_use]
+    pub fn normalized(&self) -> ViewBox {
+        let mut view_box = *self;
+        if view_box.width < 0.0 {
+            view_box.min_x += view_box.width;
+            view_box.width = -view_box.width;
+        }
+        if view_box.height < 0.0 {
+            view_box.min_y += view_box.height;
+            view_box.height = -view_box.height;
+        }
+        view_box
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+/// Byte ranges of the attributes read by this crate in the parsed input,
+/// e.g. for highlighting them in an editor.
+///
+/// Each range covers the whole attribute, i.e. `width="10px"`.
+/// Fields are `None` if the attribute is absent.
+pub struct AttributeRanges {
+    /// The range of the `viewBox` attribute
+    pub view_box: Option<Range<usize>>,
+    /// The range of the `width` attribute
+    pub width: Option<Range<usize>>,
+    /// The range of the `height` attribute
+    pub height: Option<Range<usize>>,
+}
+
+impl AttributeRanges {
+    /// Collect the attribute ranges of an element
+    fn from_element(elem: roxmltree::Node<'_, '_>) -> AttributeRanges {
+        let range = |name: &str| {
+            elem.attributes()
+                .find(|attr| attr.namespace().is_none() && attr.name() == name)
+                .map(|attr| attr.range())
+        };
+        AttributeRanges {
+            view_box: range("viewBox"),
+            width: range("width"),
+            height: range("height"),
+        }
+    }
+}
+
+/// Information about the descendants of the root element, collected in a
+/// single pass over the document
+#[derive(Default)]
+struct Descendants {
+    /// Tag names and ids of all descendants which have an `id`
+    defined_ids: Vec<(String, String)>,
+    /// The references of all `<image>` elements
+    images: Vec<String>,
+    /// Whether there are any `<image>` elements
+    has_raster: bool,
+    /// The references of all `<use>` elements, without the `#` of ids
+    use_refs: Vec<String>,
+}
+
+impl Descendants {
+    /// Walk the descendants of an element once
+    fn from_element(elem: roxmltree::Node<'_, '_>) -> Descendants {
+        let mut descendants = Descendants::default();
+        for node in elem.descendants().filter(roxmltree::Node::is_element) {
+            if let Some(id) = node.attribute("id").filter(|_| node != elem) {
+                let tag = node.tag_name().name().to_string();
+                descendants.defined_ids.push((tag, id.to_string()));
+            }
+            match node.tag_name().name() {
+                "image" => {
+                    descendants.has_raster = true;
+                    descendants
+                        .images
+                        .extend(href(node).map(ToString::to_string));
+                }
+                "use" => descendants.use_refs.extend(
+                    href(node).map(|href| href.strip_prefix('#').unwrap_or(href).to_string()),
+                ),
+                _ => {}
+            }
+        }
+        descendants
+    }
+}
+
+/// Split a whitespace-separated attribute value into its items
+fn split_list(value: Option<&str>) -> Vec<String> {
+    value
+        .map(|value| value.split_whitespace().map(ToString::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// Make sure that an input is not larger than `max_bytes`
+fn check_size(len: u64, max_bytes: usize) -> Result<(), MetadataError> {
+    if len > max_bytes as u64 {
+        return Err(MetadataError::with_kind(
+            ErrorKind::InputTooLarge,
+            &format!("Input too large: {len} bytes exceeds the limit of {max_bytes} bytes"),
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the ratio if it is positive and finite
+fn positive_ratio(ratio: f64) -> Option<f64> {
+    (ratio.is_finite() && ratio > 0.0).then_some(ratio)
+}
+
+/// Parse an `aspect-ratio` value like `16 / 9` or `1.777`.
+/// The `auto` keyword is ignored, as the root element has no natural
+/// aspect ratio other than the declared one.
+fn parse_aspect_ratio(s: &str) -> Option<f64> {
+    let ratio = s
+        .split_whitespace()
+        .filter(|token| !token.eq_ignore_ascii_case("auto"))
+        .collect::<String>();
+    let ratio = match ratio.split_once('/') {
+        Some((width, height)) => width.parse::<f64>().ok()? / height.parse::<f64>().ok()?,
+        None => ratio.parse::<f64>().ok()?,
+    };
+    positive_ratio(ratio)
+}
+
+/// Error for an attribute with an invalid value
+fn invalid_attribute(name: &str, e: &MetadataError) -> MetadataError {
+    MetadataError::with_kind(e.kind(), &format!("Invalid `{name}` attribute: {e}"))
+        .with_source_of(e)
+}
+
+/// Parse a viewBox according to the options.
+/// Returns the viewBox and whether it had to be recovered.
+fn parse_view_box(val: &str, options: ParseOptions) -> Result<(ViewBox, bool), MetadataError> {
+    let e = match ViewBox::try_from(val) {
+        Ok(view_box) => return Ok((view_box, false)),
+        Err(e) => e,
+    };
+    // Try the heuristic for decimal commas first, as general recovery
+    // would read `0,0 0,0 96,5 105,2` as `0 0 0 0`
+    let mut recovered = None;
+    if options.parse_euro_decimals {
+        recovered = ViewBox::from_euro_decimals(val);
+    }
+    if recovered.is_none() && options.recover {
+        recovered = ViewBox::recover(val);
+    }
+    recovered.map(|v| (v, true)).ok_or(e)
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+/// Statistics about a parse, see [`Metadata::parse_with_stats`].
+pub struct ParseStats {
+    /// How long parsing took
+    pub duration: Duration,
+    /// The number of elements in the document, including the root
+    pub element_count: usize,
+    /// The length of the input in bytes
+    pub source_len: usize,
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[non_exhaustive]
+/// How the metadata of a document was obtained,
+/// see [`Metadata::parse_diagnostic`].
+pub enum ParsePath {
+    /// The whole document was parsed as XML
+    FullDom,
+    /// The document was parsed as XML, but a malformed attribute value
+    /// had to be recovered (see [`ParseOptions::recover`])
+    Recovered,
+    /// The document isn't valid XML, e.g. because it was cut off, so only
+    /// the root start tag was parsed
+    HeaderFastPath,
+    /// The root start tag isn't valid XML either and was read with
+    /// HTML-like rules, see [`Metadata::parse_relaxed`]
+    Relaxed,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
+/// A problem found while parsing which doesn't prevent extracting
+/// metadata, see [`Metadata::parse_verbose`].
+pub enum Warning {
+    /// An attribute has an invalid value and was ignored
+    InvalidAttribute {
+        /// The name of the attribute
+        name: &'static str,
+        /// Why the value is invalid
+        message: String,
+    },
+    /// The aspect ratio of `width` and `height` differs from the one of the
+    /// viewBox, so the image gets letterboxed (or distorted with
+    /// `preserveAspectRatio="none"`), which is often an authoring error.
+    /// See [`Metadata::aspect_ratio_mismatch`].
+    AspectRatioMismatch {
+        /// The absolute difference between the aspect ratios
+        difference: f64,
+    },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::InvalidAttribute { name, message } => {
+                write!(f, "Ignored invalid `{name}` attribute: {message}")
+            }
+            Warning::AspectRatioMismatch { difference } => write!(
+                f,
+                "The aspect ratio of width and height differs from the viewBox by {difference}"
+            ),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+#[non_exhaustive]
+/// How serious a [`LintIssue`] is.
+pub enum Severity {
+    /// The image works, but likely not as intended
+    Warning,
+    /// The image is invalid or renders differently across renderers
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+/// A structural problem of an image, see [`Metadata::lint`].
+pub struct LintIssue {
+    /// How serious the problem is
+    pub severity: Severity,
+    /// A stable identifier of the check, e.g. `missing-view-box`
+    pub code: &'static str,
+    /// A description of the problem
+    pub message: String,
+}
+
+impl LintIssue {
+    /// Create a new issue
+    fn new(severity: Severity, code: &'static str, message: impl Into<String>) -> LintIssue {
+        LintIssue {
+            severity,
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}]: {}", self.severity, self.code, self.message)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+/// A field which differs between two `Metadata` values,
+/// see [`Metadata::diff`].
+pub struct FieldDiff {
+    /// The name of the field, e.g. `width`
+    pub field: &'static str,
+    /// The value on the left side, or `None` if it is not set
+    pub left: Option<String>,
+    /// The value on the right side, or `None` if it is not set
+    pub right: Option<String>,
+}
+
+#[cfg(feature = "url")]
+#[derive(Debug, PartialEq, Eq, Clone)]
+/// A reference to an external image, see [`Metadata::image_refs`].
+pub struct ImageRef {
+    /// The reference as written, e.g. `icons/foo.png`
+    pub href: String,
+    /// The reference resolved against the base URL, e.g.
+    /// `https://example.com/icons/foo.png`, or `None` if there is no base
+    /// URL or the reference can't be resolved
+    pub resolved: Option<String>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Hash, Copy, Clone)]
+/// Where [`Metadata::resolve_width`] and [`Metadata::resolve_height`]
+/// take the size of the image from. Renderers disagree on this when
+/// the attributes and the viewBox conflict.
+pub enum DimensionSource {
+    /// Only use the `width` and `height` attributes. A missing attribute
+    /// can't be resolved.
+    Attributes,
+    /// Only use the extents of the viewBox, ignoring the attributes
+    ViewBox,
+    /// Use the `width` and `height` attributes, and the extents of the
+    /// viewBox for missing attributes. This is what the SVG spec does:
+    /// the attributes set the size, and the viewBox only sets up the
+    /// coordinate system.
+    #[default]
+    AttributesThenViewBox,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+/// Options for resolving dimensions to pixels,
+/// see [`Metadata::resolve_width`].
+pub struct ResolveOptions {
+    /// Dots per inch, used for absolute units like `cm` or `pt`.
+    /// Defaults to 96, the CSS reference resolution. Fractional values
+    /// like 137.5 are supported.
+    pub dpi: f64,
+    /// The font size in pixels, used for `em` and `ex`.
+    /// Defaults to `None`, so font-relative units can't be resolved
+    /// unless the root element has a `font-size` or
+    /// `use_css_default_font_size` is set.
+    pub font_size: Option<f64>,
+    /// The x-height of the font relative to its font size, used for `ex`.
+    /// Defaults to 0.5, which is the fallback CSS suggests if the
+    /// actual x-height is unknown.
+    pub ex_ratio: f64,
+    /// Round resolved sizes to whole pixels. Defaults to `false`,
+    /// so results are not rounded or truncated.
+    pub round_to_int: bool,
+    /// Fall back to the initial CSS font size of 16px if neither
+    /// `font_size` nor the `font-size` of the root element is set.
+    /// Defaults to `false`.
+    pub use_css_default_font_size: bool,
+    /// Where the size is taken from if the attributes and the viewBox
+    /// conflict. Defaults to [`DimensionSource::AttributesThenViewBox`],
+    /// as in the SVG spec.
+    pub dimension_source: DimensionSource,
+}
+
+impl Default for ResolveOptions {
+    fn default() -> Self {
+        ResolveOptions {
+            dpi: 96.0,
+            font_size: None,
+            ex_ratio: 0.5,
+            round_to_int: false,
+            use_css_default_font_size: false,
+            dimension_source: DimensionSource::default(),
+        }
+    }
+}
+
+impl ResolveOptions {
+    /// The initial font size in CSS, `medium`
+    const CSS_DEFAULT_FONT_SIZE: f64 = 16.0;
+
+    /// Convert a length to pixels. Percentages are not handled here.
+    fn length_px(&self, value: f64, unit: Unit) -> Option<f64> {
+        let factor = match unit {
+            Unit::Em => self.font_size?,
+            Unit::Ex => self.font_size? * self.ex_ratio,
+            _ => unit.scale_factor(self.dpi)?,
+        };
+        Some(value * factor)
+    }
+
+    /// Apply the rounding mode to a resolved size
+    const fn round(&self, px: f64) -> f64 {
+        if self.round_to_int {
+            px.round()
+        } else {
+            px
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+/// The orientation of an image, see [`Metadata::orientation`].
+pub enum Orientation {
+    /// Width and height are (nearly) equal
+    Square,
+    /// The image is wider than it is tall
+    Landscape,
+    /// The image is taller than it is wide
+    Portrait,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Hash, Copy, Clone)]
+/// How the viewBox is aligned within the viewport, see
+/// [`PreserveAspectRatio`].
+pub enum Align {
+    /// Scale each axis independently to fill the viewport, which may
+    /// distort the image
+    None,
+    /// Align the minimum x and y values of the viewBox with the viewport
+    XMinYMin,
+    /// Center horizontally, align to the top
+    XMidYMin,
+    /// Align to the right and the top
+    XMaxYMin,
+    /// Align to the left, center vertically
+    XMinYMid,
+    /// Center the viewBox in the viewport (the default)
+    #[default]
+    XMidYMid,
+    /// Align to the right, center vertically
+    XMaxYMid,
+    /// Align to the left and the bottom
+    XMinYMax,
+    /// Center horizontally, align to the bottom
+    XMidYMax,
+    /// Align to the right and the bottom
+    XMaxYMax,
+}
+
+impl Align {
+    /// The keywords of all alignments, as used in the attribute
+    const KEYWORDS: [(Align, &'static str); 10] = [
+        (Align::None, "none"),
+        (Align::XMinYMin, "xMinYMin"),
+        (Align::XMidYMin, "xMidYMin"),
+        (Align::XMaxYMin, "xMaxYMin"),
+        (Align::XMinYMid, "xMinYMid"),
+        (Align::XMidYMid, "xMidYMid"),
+        (Align::XMaxYMid, "xMaxYMid"),
+        (Align::XMinYMax, "xMinYMax"),
+        (Align::XMidYMax, "xMidYMax"),
+        (Align::XMaxYMax, "xMaxYMax"),
+    ];
+
+    /// Returns the fraction of the free space to put before the viewBox
+    /// on the x and y axis, e.g. `0.5` for centering
+    const fn factors(self) -> (f64, f64) {
+        match self {
+            Align::XMinYMin => (0.0, 0.0),
+            Align::XMidYMin => (0.5, 0.0),
+            Align::XMaxYMin => (1.0, 0.0),
+            Align::XMinYMid => (0.0, 0.5),
+            Align::None | Align::XMidYMid => (0.5, 0.5),
+            Align::XMaxYMid => (1.0, 0.5),
+            Align::XMinYMax => (0.0, 1.0),
+            Align::XMidYMax => (0.5, 1.0),
+            Align::XMaxYMax => (1.0, 1.0),
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Hash, Copy, Clone)]
+/// The `preserveAspectRatio` attribute, which describes how a viewBox is
+/// fitted into a viewport of a different aspect ratio.
+///
+/// The default is `xMidYMid meet`, as for a missing attribute.
+/// For more information see: <https://developer.mozilla.org/en-US/docs/Web/SVG/Attribute/preserveAspectRatio>
+pub struct PreserveAspectRatio {
+    /// How the viewBox is aligned within the viewport
+    pub align: Align,
+    /// Whether the viewBox is scaled to cover the whole viewport (`slice`),
+    /// cropping it, instead of being scaled to fit into it (`meet`)
+    pub slice: bool,
+}
+
+impl TryFrom<&str> for PreserveAspectRatio {
+    type Error = MetadataError;
+    fn try_from(s: &str) -> Result<PreserveAspectRatio, MetadataError> {
+        let invalid = || MetadataError::new(&format!("Invalid preserveAspectRatio: {s}"));
+        let mut tokens = s.split_whitespace().peekable();
+        // `defer` only applies to `<image>` elements
+        tokens.next_if_eq(&"defer");
+        let align = tokens.next().ok_or_else(invalid)?;
+        let align = Align::KEYWORDS
+            .iter()
+            .find(|(_, keyword)| *keyword == align)
+            .map(|(align, _)| *align)
+            .ok_or_else(invalid)?;
+        let slice = match tokens.next() {
+            None | Some("meet") => false,
+            Some("slice") => true,
+            Some(_) => return Err(invalid()),
+        };
+        if tokens.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(PreserveAspectRatio { align, slice })
+    }
+}
+
+impl_try_from_string!(PreserveAspectRatio, "xMinYMax slice");
+
+impl fmt::Display for PreserveAspectRatio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (_, align) = Align::KEYWORDS
+            .iter()
+            .find(|(align, _)| *align == self.align)
+            .expect("all alignments have a keyword");
+        let mode = if self.slice { "slice" } else { "meet" };
+        write!(f, "{align} {mode}")
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Hash, Copy, Clone)]
+/// Describes which fields of a `Metadata` are set.
+///
+/// Useful for bucketing large collections of SVG files.
+pub struct FieldPresence {
+    /// Whether a valid viewBox was found
+    pub has_view_box: bool,
+    /// Whether a valid width was found
+    pub has_width: bool,
+    /// Whether a valid height was found
+    pub has_height: bool,
+}
+
+impl FieldPresence {
+    /// Returns the number of fields which are set
+    #[must_use]
+    pub fn count(&self) -> usize {
+        usize::from(self.has_view_box) + usize::from(self.has_width) + usize::from(self.has_height)
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+/// Options for [`Metadata::parse_with_options`].
+pub struct ParseOptions {
+    /// Fail on invalid `viewBox`, `width`, or `height` attributes instead of
+    /// ignoring them. See [`Metadata::parse_strict`].
+    /// This includes whitespace between a number and its unit
+    /// (e.g. `width="100 px"`), which is accepted otherwise.
+    pub strict: bool,
+    /// Try to recover values from malformed attributes, which some broken
+    /// exporters produce. For example `viewBox="0 0 96 105;"` is read as
+    /// `0 0 96 105` by ignoring anything after the first four numbers,
+    /// and units are removed from `viewBox="0px 0px 96px 105px"`.
+    /// Use [`Metadata::recovered`] to check if recovery was needed.
+    pub recover: bool,
+    /// Read a `viewBox` which can't be parsed otherwise with commas as
+    /// decimal separators, e.g. `0 0 96,5 105,2`. Some tools emit those
+    /// depending on the locale, even though it's invalid SVG.
+    ///
+    /// This is a heuristic, because commas also separate numbers:
+    /// it only applies if every number contains at most one comma.
+    /// `0,0,96,105` is still read as four numbers, while `0,0 96,5` is
+    /// read as the two numbers `0.0` and `96.5` and hence rejected.
+    /// Off by default. Counts as a recovery for [`Metadata::recovered`].
+    pub parse_euro_decimals: bool,
+    /// Reject documents in which elements are nested deeper than this,
+    /// e.g. to defend against adversarial input which could otherwise
+    /// exhaust the stack. The root element has a depth of 1.
+    /// Defaults to `None`, i.e. unlimited.
+    pub max_depth: Option<usize>,
+}
+
+/// Returns the `href` attribute of an element, falling back to the
+/// deprecated `xlink:href` which is still common in older files
+fn href<'a>(node: roxmltree::Node<'a, '_>) -> Option<&'a str> {
+    node.attribute("href")
+        .or_else(|| node.attribute((XLINK_NS, "href")))
+}
+
+/// Attribute names which are common on root elements, sorted.
+/// They are shared instead of allocating a key for every parsed image.
+const COMMON_ATTRIBUTES: [&str; 32] = [
+    "aria-label",
+    "baseProfile",
+    "class",
+    "clip",
+    "clip-rule",
+    "color",
+    "contentScriptType",
+    "contentStyleType",
+    "enable-background",
+    "fill",
+    "fill-rule",
+    "focusable",
+    "font-size",
+    "height",
+    "id",
+    "inkscape:version",
+    "overflow",
+    "preserveAspectRatio",
+    "requiredExtensions",
+    "requiredFeatures",
+    "role",
+    "sodipodi:docbase",
+    "sodipodi:docname",
+    "sodipodi:version",
+    "style",
+    "version",
+    "viewBox",
+    "width",
+    "x",
+    "xml:space",
+    "y",
+    "zoomAndPan",
+];
+
+/// Returns the qualified name of an attribute, borrowing it from
+/// [`COMMON_ATTRIBUTES`] if possible
+fn intern_attribute_name(prefix: Option<&str>, name: &str) -> Cow<'static, str> {
+    // Compare without building the qualified name first
+    let qualified = || {
+        prefix
+            .into_iter()
+            .flat_map(|prefix| [prefix, ":"])
+            .chain([name])
+            .flat_map(str::bytes)
+    };
+    match COMMON_ATTRIBUTES.binary_search_by(|common| common.bytes().cmp(qualified())) {
+        Ok(index) => Cow::Borrowed(COMMON_ATTRIBUTES[index]),
+        Err(_) => Cow::Owned(String::from_utf8(qualified().collect()).expect("valid UTF-8")),
+    }
+}
+
+/// Returns all attributes of `node` by their qualified name, e.g.
+/// `xlink:href`. Namespace declarations are not attributes in this sense.
+fn attribute_map(node: roxmltree::Node<'_, '_>) -> BTreeMap<Cow<'static, str>, String> {
+    node.attributes()
+        .map(|attr| {
+            let prefix = attr.namespace().and_then(|uri| node.lookup_prefix(uri));
+            (
+                intern_attribute_name(prefix, attr.name()),
+                attr.value().to_string(),
+            )
+        })
+        .collect()
+}
+
+/// A 64-bit FNV-1a hasher. Unlike the hashers of the standard library,
+/// its output is guaranteed to stay the same, see [`Metadata::content_hash`].
+struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        StableHasher(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl StableHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    /// Strings are prefixed with their length, so that consecutive
+    /// strings can't run into each other
+    fn write_str(&mut self, s: &str) {
+        self.write_u64(s.len() as u64);
+        self.write(s.as_bytes());
+    }
+
+    /// Numbers are rounded to six decimal places first
+    fn write_f64(&mut self, value: f64) {
+        // Adding zero turns `-0.0` into `0.0`
+        let rounded = (value * 1e6).round() + 0.0;
+        self.write_u64(rounded.to_bits());
+    }
+
+    fn write_option<T>(&mut self, value: Option<&T>, write: impl FnOnce(&mut Self, &T)) {
+        match value {
+            Some(value) => {
+                self.write(&[1]);
+                write(self, value);
+            }
+            None => self.write(&[0]),
+        }
+    }
+}
+
+/// Read a whitespace-separated list of numbers which use commas as
+/// decimal separators. Returns `None` if the list doesn't look like that,
+/// i.e. if a number contains more than one comma or none of them has one.
+fn euro_decimal_values(s: &str) -> Option<Vec<f64>> {
+    let tokens: Vec<&str> = s.split_whitespace().collect();
+    if !tokens.iter().any(|t| t.contains(',')) {
+        return None;
+    }
+    tokens
+        .iter()
+        .map(|t| match t.matches(',').count() {
+            0 | 1 => t.replacen(',', ".", 1).parse::<f64>().ok(),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Extract metadata from the root element of an already parsed document.
+///
+/// This avoids parsing the input twice if the document is needed anyway,
+/// e.g. to read further attributes. Invalid attributes are ignored like in
+/// [`Metadata::parse`]. Note that documents with a DOCTYPE must be parsed
+/// with `allow_dtd` enabled in roxmltree.
+///
+/// # Example
+///
+/// ```rust
+/// let svg = r#"<svg viewBox="0 0 96 105" data-theme="dark"/>"#;
+/// let doc = roxmltree::Document::parse(svg).unwrap();
+///
+/// let meta = svg_metadata::parse_root(&doc);
+/// assert!(meta.view_box.is_some());
+/// assert_eq!(doc.root_element().attribute("data-theme"), Some("dark"));
+/// ```
+#[must_use]
+pub fn parse_root<'a>(doc: &'a roxmltree::Document<'a>) -> Metadata {
+    Metadata::from_element(doc.root_element(), ParseOptions::default())
+}
+
+/// Call `callback` for every element in the document, in document order,
+/// with its tag name and its attributes as `(name, value)` pairs.
+///
+/// This is a lower-level alternative to [`Metadata::parse`] for reading
+/// information this crate doesn't model, without depending on an XML
+/// parser directly. Names are local names, i.e. without their namespace
+/// prefix, and entities in values are resolved.
+///
+/// # Example
+///
+/// ```rust
+/// let svg = r#"<svg><rect width="10"/><g><rect width="20"/></g></svg>"#;
+/// let mut widths = Vec::new();
+/// svg_metadata::scan(svg, |name, attributes| {
+///     if name == "rect" {
+///         if let Some((_, width)) = attributes.iter().find(|(k, _)| *k == "width") {
+///             widths.push(width.to_string());
+///         }
+///     }
+/// })
+/// .unwrap();
+/// assert_eq!(widths, ["10", "20"]);
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if the document is not well-formed XML. The callback
+/// is not called in that case.
+pub fn scan<F: FnMut(&str, &[(&str, &str)])>(
+    input: &str,
+    mut callback: F,
+) -> Result<(), MetadataError> {
+    let doc = parse_document(input)?;
+    // Reused for all elements
+    let mut attributes = Vec::new();
+    for node in doc.descendants().filter(roxmltree::Node::is_element) {
+        attributes.clear();
+        attributes.extend(node.attributes().map(|attr| (attr.name(), attr.value())));
+        callback(node.tag_name().name(), &attributes);
+    }
+    Ok(())
+}
+
+/// Parse an XML document with the options used throughout this crate
+fn parse_document(input: &str) -> Result<roxmltree::Document<'_>, MetadataError> {
+    let doc = roxmltree::Document::parse_with_options(
+        input,
+        roxmltree::ParsingOptions {
+            // Allow DTDs (e.g. `<!DOCTYPE svg PUBLIC "-//W3C//DTD SVG 1.1//EN"`)
+            // See [`roxmltree` docs](https://docs.rs/roxmltree/latest/roxmltree/struct.ParsingOptions.html#structfield.allow_dtd)
+            // for more info
+            allow_dtd: true,
+            ..Default::default()
+        },
+    )?;
+    Ok(doc)
+}
+
+#[derive(Debug, PartialEq, Clone)]
+/// Contains all metadata that was
+/// extracted from an SVG image.
+///
+/// Unlike the dimension types, `Metadata` is not `Copy`, because it owns
+/// strings like the root `fill` and `color` attributes.
+pub struct Metadata {
+    /// The viewBox of the SVG image
+    /// A viewBox is a rectangle that defines the dimensions of the image.
+    /// For more information see: <https://developer.mozilla.org/en-US/docs/Web/SVG/Attribute/viewBox>
+    pub view_box: Option<ViewBox>,
+    /// The width of the SVG image
+    pub width: Option<Width>,
+    /// The height of the SVG image
+    pub height: Option<Height>,
+    /// The `fill` presentation attribute of the root element, as written
+    pub fill: Option<String>,
+    /// The `color` presentation attribute of the root element, as written
+    pub color: Option<String>,
+    /// The `overflow` attribute of the root element (e.g. `visible` or
+    /// `hidden`), as written. Describes whether content outside of the
+    /// viewport is clipped.
+    pub overflow: Option<String>,
+    /// The deprecated `clip` attribute of the root element, as written
+    pub clip: Option<String>,
+    /// The URIs listed in the `requiredExtensions` attribute of the root
+    /// element. A renderer which doesn't support all of them won't
+    /// render the image.
+    pub required_extensions: Vec<String>,
+    /// The URIs listed in the `requiredFeatures` attribute of the root
+    /// element (deprecated in SVG 2)
+    pub required_features: Vec<String>,
+    /// The local name of the root element
+    root_tag: String,
+    /// The `width` attribute, including values which can't be
+    /// represented as a `Width`
+    width_dimension: Option<Dimension>,
+    /// The `height` attribute, including values which can't be
+    /// represented as a `Height`
+    height_dimension: Option<Dimension>,
+    /// Which dimensions have no unit. `width` and `height` report such
+    /// values in `em`, but they are in user units (pixels).
+    unitless: Unitless,
+    /// Whether any attribute had to be recovered from a malformed value
+    recovered: bool,
+    /// Positions of the attributes in the input
+    attribute_ranges: AttributeRanges,
+    /// Tag names and ids of all descendants which have an `id`
+    defined_ids: Vec<(String, String)>,
+    /// The `font-size` attribute of the root element
+    font_size: Option<(f64, Unit)>,
+    /// The `viewBox` attribute, as written
+    raw_view_box: Option<String>,
+    /// The `width` attribute, as written
+    raw_width: Option<String>,
+    /// The `height` attribute, as written
+    raw_height: Option<String>,
+    /// The references of all `<image>` elements
+    images: Vec<String>,
+    /// The references of all `<use>` elements, without the `#` of ids
+    use_refs: Vec<String>,
+    /// Whether there are any `<image>` elements
+    has_raster: bool,
+    /// The `aspect-ratio` attribute of the root element
+    declared_aspect_ratio: Option<f64>,
+    /// The namespaces in scope on the root element, by prefix
+    namespaces: BTreeMap<String, String>,
+    /// All attributes of the root element, as written
+    attributes: BTreeMap<Cow<'static, str>, String>,
+    /// The class names in the `class` attribute of the root element
+    classes: Vec<String>,
+    /// The URL the document was loaded from, to resolve relative references
+    base_url: Option<String>,
+}
+
+impl Metadata {
+    /// Parse an SVG file and extract metadata from it.
+    ///
+    /// The file must be UTF-8 encoded, unless the `encoding` feature is
+    /// enabled. In that case the encoding is detected from a byte order
+    /// mark or the XML declaration. Use [`Metadata::parse_file_auto`] for
+    /// files which may be gzip-compressed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::{Metadata, ViewBox};
+    ///
+    /// let meta = Metadata::parse_file("fixtures/test.svg").unwrap();
+    ///     assert_eq!(
+    ///     meta.view_box,
+    ///     Some(ViewBox {
+    ///         min_x: 0.0,
+    ///         min_y: 0.0,
+    ///         width: 96.0,
+    ///         height: 105.0
+    ///     })
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or if the SVG data is invalid.
+    pub fn parse_file<T: Into<PathBuf>>(path: T) -> Result<Metadata, MetadataError> {
+        let data = fs::read(path.into())?;
+        Self::parse(decode::decode(data)?)
+    }
+
+    /// Parse an SVG file which may be gzip-compressed and extract
+    /// metadata from it.
+    ///
+    /// Compression is detected from the content, regardless of the file
+    /// extension, so `.svg` and `.svgz` files can be handled alike.
+    /// Reading compressed files requires the `svgz` feature.
+    /// The data is handled like the input of [`Metadata::parse_data`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let meta = Metadata::parse_file_auto("fixtures/test.svg").unwrap();
+    /// assert_eq!(meta, Metadata::parse_file("fixtures/test.svg").unwrap());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or decompressed, if it
+    /// is compressed and the `svgz` feature is disabled, or if the SVG
+    /// data is invalid.
+    pub fn parse_file_auto<T: Into<PathBuf>>(path: T) -> Result<Metadata, MetadataError> {
+        Self::parse_owned_data(fs::read(path.into())?)
+    }
+
+    /// Parse an already opened SVG file and extract metadata from it.
+    ///
+    /// Useful if the file needs to be opened with custom options.
+    /// Reading starts at the current position of the file handle.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::fs::File;
+    /// use svg_metadata::Metadata;
+    ///
+    /// let mut file = File::open("fixtures/test.svg").unwrap();
+    /// let meta = Metadata::parse_open_file(&mut file).unwrap();
+    /// assert!(meta.view_box.is_some());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or if the SVG data is invalid.
+    pub fn parse_open_file(file: &mut fs::File) -> Result<Metadata, MetadataError> {
+        use std::io::Read;
+
+        let mut data = Vec::new();
+        file.read_to_end(&mut data)?;
+        Self::parse_owned_data(data)
+    }
+
+    /// Fetch an SVG image from a URL and extract metadata from it.
+    ///
+    /// Requires the `http` feature.
+    /// Compressed responses are decoded if the server sets a
+    /// `Content-Encoding` header. Compressed SVG files (`.svgz`)
+    /// additionally require the `svgz` feature.
+    /// Responses larger than 10 MiB are refused, see
+    /// [`Metadata::parse_url_with_limit`] for a different limit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, if the response is too large,
+    /// if it is a compressed SVG file and the `svgz` feature is disabled,
+    /// or if the SVG data is invalid.
+    #[cfg(feature = "http")]
+    pub fn parse_url(url: &str) -> Result<Metadata, MetadataError> {
+        Self::parse_url_with_limit(url, MAX_RESPONSE_BYTES)
+    }
+
+    /// Fetch an SVG image like [`Metadata::parse_url`], but stop reading
+    /// the response after `max_bytes`.
+    ///
+    /// Requires the `http` feature.
+    /// The limit applies to the decoded body, so compressed responses
+    /// can't exceed it either.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, if the response is larger
+    /// than `max_bytes`, if it is a compressed SVG file and the `svgz`
+    /// feature is disabled, or if the SVG data is invalid.
+    #[cfg(feature = "http")]
+    pub fn parse_url_with_limit(url: &str, max_bytes: usize) -> Result<Metadata, MetadataError> {
+        use std::io::Read;
+
+        let response = ureq::get(url).call()?;
+        let mut data = Vec::new();
+        // Read one byte more than allowed to detect larger responses
+        response
+            .into_reader()
+            .take(max_bytes as u64 + 1)
+            .read_to_end(&mut data)?;
+        if data.len() > max_bytes {
+            return Err(MetadataError::with_kind(
+                ErrorKind::InputTooLarge,
+                &format!("Response too large: exceeds the limit of {max_bytes} bytes"),
+            ));
+        }
+
+        Self::parse_owned_data(data)
+    }
+
+    /// Parse SVG data like [`Metadata::parse`] and remember the URL it was
+    /// loaded from, so that relative image references can be resolved
+    /// with [`Metadata::image_refs`].
+    ///
+    /// Requires the `url` feature.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let svg = r#"<svg><image href="icons/foo.png"/></svg>"#;
+    /// let meta = Metadata::parse_with_base_url(svg, "https://example.com/a/b.svg").unwrap();
+    /// assert_eq!(
+    ///     meta.image_refs()[0].resolved.as_deref(),
+    ///     Some("https://example.com/a/icons/foo.png")
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `base_url` is not an absolute URL or if the SVG
+    /// data is invalid.
+    #[cfg(feature = "url")]
+    pub fn parse_with_base_url<T: AsRef<str>>(
+        input: T,
+        base_url: &str,
+    ) -> Result<Metadata, MetadataError> {
+        let base_url = url::Url::parse(base_url)
+            .map_err(|e| MetadataError::new(&format!("Invalid base URL: {e}")))?;
+        Ok(Metadata {
+            base_url: Some(base_url.into()),
+            ..Self::parse(input)?
+        })
+    }
+
+    /// Parse raw SVG data, e.g. the contents of a file, and extract
+    /// metadata from it. This is what all other entry points which read
+    /// bytes use.
+    ///
+    /// The data is processed in this order:
+    ///
+    /// 1. If it starts with the gzip magic bytes, it is decompressed.
+    ///    This requires the `svgz` feature.
+    /// 2. It is decoded into text. With the `encoding` feature, the
+    ///    encoding is taken from a byte order mark or the XML declaration,
+    ///    falling back to UTF-8. Without it, the data must be UTF-8.
+    /// 3. The text is parsed like in [`Metadata::parse`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::{Metadata, ViewBox};
+    ///
+    /// let meta = Metadata::parse_data(br#"<svg viewBox="0 0 10 20"/>"#).unwrap();
+    /// assert_eq!(meta.view_box, Some(ViewBox::from_dimensions(10.0, 20.0)));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data cannot be decompressed or decoded, if
+    /// it is compressed and the `svgz` feature is disabled, or if the SVG
+    /// data is invalid.
+    pub fn parse_data(data: &[u8]) -> Result<Metadata, MetadataError> {
+        Self::parse_owned_data(data.to_vec())
+    }
+
+    /// Parse an SVG image from a `data:image/svg+xml` URI, as found in
+    /// CSS, HTML, or JSON.
+    ///
+    /// Both base64 (`data:image/svg+xml;base64,...`) and percent-encoded
+    /// payloads (`data:image/svg+xml,%3Csvg...`) are supported. The
+    /// decoded data is parsed like in [`Metadata::parse_data`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let meta = Metadata::parse_data_uri("data:image/svg+xml;base64,PHN2ZyB3aWR0aD0iMSIvPg==").unwrap();
+    /// assert_eq!(meta.width(), Some(1.0));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URI isn't a data URI, if its media type
+    /// isn't `image/svg+xml`, if the payload cannot be decoded, or if the
+    /// SVG data is invalid.
+    pub fn parse_data_uri(uri: &str) -> Result<Metadata, MetadataError> {
+        Self::parse_owned_data(decode::data_uri(uri)?)
+    }
+
+    /// Like [`Metadata::parse_data`], but avoids copying the data
+    fn parse_owned_data(data: Vec<u8>) -> Result<Metadata, MetadataError> {
+        Self::parse(decode::decode(decode::decompress(data)?)?)
+    }
+
+    /// Parse SVG data and extract metadata from it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::{Metadata, ViewBox, Width, Height, Unit};
+    ///
+    /// let svg = r#"<svg viewBox="0 1 99 100" width="2em" height="10cm" xmlns="http://www.w3.org/2000/svg">
+    ///  <rect x="0" y="0" width="100%" height="100%"/>
+    /// </svg>"#;
+    ///
+    /// let meta = Metadata::parse(svg).unwrap();
+    /// assert_eq!(
+    ///    meta.view_box,
+    ///    Some(ViewBox {
+    ///      min_x: 0.0,
+    ///      min_y: 1.0,
+    ///      width: 99.0,
+    ///      height: 100.0
+    ///    })
+    /// );
+    /// assert_eq!(
+    ///   meta.width,
+    ///   Some(Width {
+    ///     width: 2.0,
+    ///     unit: Unit::Em
+    ///   })
+    /// );
+    /// assert_eq!(
+    ///  meta.height,
+    ///  Some(Height {
+    ///    height: 10.0,
+    ///    unit: Unit::Cm
+    ///   })
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the SVG data is invalid.
+    pub fn parse<T: AsRef<str>>(input: T) -> Result<Metadata, MetadataError> {
+        Self::parse_with_options(input, ParseOptions::default())
+    }
+
+    /// Parse SVG data which is either borrowed or owned.
+    ///
+    /// The parser only borrows the input while parsing, and the returned
+    /// `Metadata` owns all of its data. So an owned `String` is simply
+    /// kept alive until parsing is done and dropped afterwards,
+    /// without being copied.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::borrow::Cow;
+    /// use svg_metadata::Metadata;
+    ///
+    /// let borrowed = Metadata::parse_cow(Cow::Borrowed(r#"<svg width="1"/>"#)).unwrap();
+    /// let owned = Metadata::parse_cow(Cow::Owned(r#"<svg width="1"/>"#.to_string())).unwrap();
+    /// assert_eq!(borrowed, owned);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the SVG data is invalid.
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn parse_cow(input: Cow<'_, str>) -> Result<Metadata, MetadataError> {
+        Self::parse(&input)
+    }
+
+    /// Parse SVG data like [`Metadata::parse`], but return `None` instead
+    /// of an error, e.g. for use in `filter_map`.
+    ///
+    /// As with `parse`, invalid attributes are ignored, so `None` is only
+    /// returned if the XML itself is invalid.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let inputs = [r#"<svg viewBox="0 0 10 10"/>"#, "<svg", r#"<svg width="wide"/>"#];
+    /// let parsed: Vec<Metadata> = inputs.iter().filter_map(Metadata::try_parse).collect();
+    /// assert_eq!(parsed.len(), 2);
+    /// ```
+    #[must_use]
+    pub fn try_parse<T: AsRef<str>>(input: T) -> Option<Metadata> {
+        Self::parse(input).ok()
+    }
+
+    /// Parse SVG data like [`Metadata::parse`], but refuse inputs larger
+    /// than `max_bytes` before parsing them.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::{ErrorKind, Metadata};
+    ///
+    /// let svg = r#"<svg width="10px"/>"#;
+    /// assert!(Metadata::parse_with_limit(svg, 1024).is_ok());
+    ///
+    /// let err = Metadata::parse_with_limit(svg, 10).unwrap_err();
+    /// assert_eq!(err.kind(), ErrorKind::InputTooLarge);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input is larger than `max_bytes`
+    /// or if the SVG data is invalid.
+    pub fn parse_with_limit<T: AsRef<str>>(
+        input: T,
+        max_bytes: usize,
+    ) -> Result<Metadata, MetadataError> {
+        let input = input.as_ref();
+        check_size(input.len() as u64, max_bytes)?;
+        Self::parse(input)
+    }
+
+    /// Parse an SVG file like [`Metadata::parse_file`], but refuse files
+    /// larger than `max_bytes` without reading them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file is larger than `max_bytes`, if it
+    /// cannot be read, or if the SVG data is invalid.
+    pub fn parse_file_with_limit<T: Into<PathBuf>>(
+        path: T,
+        max_bytes: usize,
+    ) -> Result<Metadata, MetadataError> {
+        let path = path.into();
+        check_size(fs::metadata(&path)?.len(), max_bytes)?;
+        Self::parse_file(path)
+    }
+
+    /// Parse multiple SVG documents at once.
+    ///
+    /// Returns one result per input, in the same order.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let inputs = vec![r#"<svg width="1"/>"#, "<svg", r#"<svg width="3"/>"#];
+    /// let results = Metadata::parse_batch(inputs);
+    /// assert_eq!(results.len(), 3);
+    /// assert!(results[1].is_err());
+    /// ```
+    pub fn parse_batch<I, S>(inputs: I) -> Vec<Result<Metadata, MetadataError>>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        inputs.into_iter().map(Self::parse).collect()
+    }
+
+    /// Parse SVG data like [`Metadata::parse`], but fail on invalid
+    /// `viewBox`, `width`, or `height` attributes instead of ignoring them.
+    ///
+    /// Useful for validating SVG files, e.g. in CI.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let svg = r#"<svg viewBox="0 0 10"/>"#;
+    /// assert_eq!(Metadata::parse(svg).unwrap().view_box, None);
+    /// assert!(Metadata::parse_strict(svg).is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the SVG data or any of the attributes is invalid.
+    pub fn parse_strict<T: AsRef<str>>(input: T) -> Result<Metadata, MetadataError> {
+        let options = ParseOptions {
+            strict: true,
+            ..ParseOptions::default()
+        };
+        Self::parse_with_options(input, options)
+    }
+
+    /// Parse SVG data with the given options and extract metadata from it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::{Metadata, ParseOptions, ViewBox};
+    ///
+    /// let options = ParseOptions {
+    ///     recover: true,
+    ///     ..ParseOptions::default()
+    /// };
+    /// let meta = Metadata::parse_with_options(r#"<svg viewBox="0 0 96 105;"/>"#, options).unwrap();
+    /// assert_eq!(meta.view_box, Some(ViewBox::try_from("0 0 96 105").unwrap()));
+    /// assert!(meta.recovered());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the SVG data is invalid
+    /// or, in strict mode, if any of the attributes is invalid.
+    pub fn parse_with_options<T: AsRef<str>>(
+        input: T,
+        options: ParseOptions,
+    ) -> Result<Metadata, MetadataError> {
+        let input = input.as_ref();
+        if let Some(max_depth) = options.max_depth {
+            // Checked before parsing, as building the tree of deeply
+            // nested input can exhaust the stack
+            if scan::depth_exceeds(input, max_depth) {
+                return Err(MetadataError::with_kind(
+                    ErrorKind::MaxDepthExceeded,
+                    &format!("Elements are nested deeper than {max_depth} levels"),
+                ));
+            }
+        }
+        let doc = parse_document(input)?;
+        let root = doc.root_element();
+        if options.strict {
+            Self::check_strict(root, options)?;
+        }
+        Ok(Self::from_element(root, options))
+    }
+
+    /// Extract metadata from the root start tag, if it is complete within
+    /// the first `max_scan_bytes` of the input.
+    ///
+    /// This is a cheap pre-scan for huge inputs: the rest of the input is
+    /// not looked at, and `Ok(None)` is returned if the root start tag
+    /// doesn't end within the window. As the content of the root element
+    /// is skipped, information about descendants (e.g.
+    /// [`Metadata::images`]) is empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let svg = r#"<svg viewBox="0 0 10 10"><rect width="10" height="10"/></svg>"#;
+    /// let meta = Metadata::parse_prefix(svg, 64).unwrap().unwrap();
+    /// assert!(meta.view_box.is_some());
+    /// assert_eq!(Metadata::parse_prefix(svg, 16).unwrap(), None);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the root start tag is invalid.
+    pub fn parse_prefix(
+        input: &str,
+        max_scan_bytes: usize,
+    ) -> Result<Option<Metadata>, MetadataError> {
+        let mut window = max_scan_bytes.min(input.len());
+        while !input.is_char_boundary(window) {
+            window -= 1;
+        }
+        let Some(root) = scan::root_tag(&input[..window]) else {
+            return Ok(None);
+        };
+        let head = &input[..root.end];
+        if root.self_closing {
+            return Self::parse(head).map(Some);
+        }
+        Self::parse(format!("{head}</{}>", root.name)).map(Some)
+    }
+
+    /// Parse SVG data like [`Metadata::parse`], but fall back to the root
+    /// start tag if the document is malformed, e.g. because a download
+    /// was cut off.
+    ///
+    /// As in [`Metadata::parse_prefix`], information about descendants is
+    /// only available if the whole document could be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let truncated = r#"<svg viewBox="0 0 10 10"><path d="M 0,0 L"#;
+    /// assert!(Metadata::parse(truncated).is_err());
+    /// let meta = Metadata::parse_allow_partial_xml(truncated).unwrap();
+    /// assert!(meta.view_box.is_some());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the full parse if the root start tag is
+    /// incomplete or invalid as well.
+    pub fn parse_allow_partial_xml<T: AsRef<str>>(input: T) -> Result<Metadata, MetadataError> {
+        let input = input.as_ref();
+        match Self::parse(input) {
+            Ok(meta) => Ok(meta),
+            Err(e) => match Self::parse_prefix(input, input.len()) {
+                Ok(Some(meta)) => Ok(meta),
+                _ => Err(e),
+            },
+        }
+    }
+
+    /// Parse SVG data like [`Metadata::parse`], but fall back to a tolerant
+    /// scan of the root start tag if the document isn't valid XML, e.g. for
+    /// hand-written or templated snippets like `<svg width=100 height=50>`.
+    ///
+    /// Like in HTML, attribute values in the root start tag may be
+    /// unquoted, attributes without a value are empty, and the first of
+    /// duplicate attributes wins. As in [`Metadata::parse_prefix`],
+    /// information about descendants is only available if the whole
+    /// document could be parsed. Attribute ranges are only available
+    /// for valid XML.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::{Metadata, Unit, Width};
+    ///
+    /// let svg = "<svg width=100px height=50px viewBox='0 0 100 50'><rect></svg>";
+    /// assert!(Metadata::parse(svg).is_err());
+    /// let meta = Metadata::parse_relaxed(svg).unwrap();
+    /// assert_eq!(meta.width, Some(Width::new(100.0, Unit::Px).unwrap()));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the full parse if the root start tag can't be
+    /// read either.
+    pub fn parse_relaxed<T: AsRef<str>>(input: T) -> Result<Metadata, MetadataError> {
+        let input = input.as_ref();
+        match Self::parse(input) {
+            Ok(meta) => Ok(meta),
+            Err(e) => Self::parse_relaxed_root(input).ok_or(e),
+        }
+    }
+
+    /// Read the root start tag with HTML-like rules and parse it on its
+    /// own, see [`Metadata::parse_relaxed`]
+    fn parse_relaxed_root(input: &str) -> Option<Metadata> {
+        use std::fmt::Write;
+
+        let root = scan::root_tag(input)?;
+        let closing = if root.self_closing { "/>" } else { ">" };
+        let attributes = &input[root.start + 1 + root.name.len()..root.end - closing.len()];
+
+        // Rebuild the start tag as valid XML
+        let mut tag = format!("<{}", root.name);
+        let mut seen = Vec::new();
+        for (name, value) in scan::relaxed_attributes(attributes) {
+            if seen.contains(&name) {
+                continue;
+            }
+            seen.push(name);
+            let value = value.replace('<', "&lt;").replace('"', "&quot;");
+            write!(tag, " {name}=\"{value}\"").expect("writing to a string can't fail");
+        }
+        tag.push_str("/>");
+        let meta = Self::parse(tag).ok()?;
+        Some(Metadata {
+            attribute_ranges: AttributeRanges::default(),
+            ..meta
+        })
+    }
+
+    /// Parse SVG data with every fallback this crate has and also return
+    /// which of them produced the metadata, e.g. to debug or test how a
+    /// file is handled.
+    ///
+    /// The document is parsed with [`ParseOptions::recover`] first. If
+    /// it isn't valid XML, the root start tag is read on its own, like in
+    /// [`Metadata::parse_allow_partial_xml`], and finally with the
+    /// HTML-like rules of [`Metadata::parse_relaxed`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::{Metadata, ParsePath};
+    ///
+    /// let (_, path) = Metadata::parse_diagnostic(r#"<svg viewBox="0 0 10 10"/>"#).unwrap();
+    /// assert_eq!(path, ParsePath::FullDom);
+    /// let (meta, path) = Metadata::parse_diagnostic(r#"<svg viewBox="0 0 10 10"><path d="M"#).unwrap();
+    /// assert_eq!(path, ParsePath::HeaderFastPath);
+    /// assert!(meta.view_box.is_some());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the full parse if none of the fallbacks can
+    /// read the root start tag either.
+    pub fn parse_diagnostic<T: AsRef<str>>(
+        input: T,
+    ) -> Result<(Metadata, ParsePath), MetadataError> {
+        let input = input.as_ref();
+        let options = ParseOptions {
+            recover: true,
+            ..ParseOptions::default()
+        };
+        let e = match Self::parse_with_options(input, options) {
+            Ok(meta) if meta.recovered() => return Ok((meta, ParsePath::Recovered)),
+            Ok(meta) => return Ok((meta, ParsePath::FullDom)),
+            Err(e) => e,
+        };
+        if let Ok(Some(meta)) = Self::parse_prefix(input, input.len()) {
+            return Ok((meta, ParsePath::HeaderFastPath));
+        }
+        Self::parse_relaxed_root(input)
+            .map(|meta| (meta, ParsePath::Relaxed))
+            .ok_or(e)
+    }
+
+    /// Parse SVG data like [`Metadata::parse`] and also return statistics
+    /// about the parse, e.g. for monitoring.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let svg = r#"<svg viewBox="0 0 10 10"><rect width="10" height="10"/></svg>"#;
+    /// let (meta, stats) = Metadata::parse_with_stats(svg).unwrap();
+    /// assert_eq!(stats.element_count, 2);
+    /// assert_eq!(stats.source_len, svg.len());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the SVG data is invalid.
+    pub fn parse_with_stats<T: AsRef<str>>(
+        input: T,
+    ) -> Result<(Metadata, ParseStats), MetadataError> {
+        let input = input.as_ref();
+        let start = Instant::now();
+        let doc = parse_document(input)?;
+        let meta = Self::from_element(doc.root_element(), ParseOptions::default());
+        let element_count = doc
+            .descendants()
+            .filter(roxmltree::Node::is_element)
+            .count();
+        let stats = ParseStats {
+            duration: start.elapsed(),
+            element_count,
+            source_len: input.len(),
+        };
+        Ok((meta, stats))
+    }
+
+    /// Parse SVG data like [`Metadata::parse`] and also return warnings
+    /// about problems which don't prevent parsing.
+    ///
+    /// Warnings are reported for attributes which are ignored because they
+    /// are invalid, and for a mismatch between the aspect ratios of the
+    /// dimensions and the viewBox (by more than 0.01) unless
+    /// `preserveAspectRatio="none"` makes the distortion intentional.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::{Metadata, Warning};
+    ///
+    /// let svg = r#"<svg viewBox="0 0 100 100" width="200px" height="100px"/>"#;
+    /// let (meta, warnings) = Metadata::parse_verbose(svg).unwrap();
+    /// assert_eq!(warnings, vec![Warning::AspectRatioMismatch { difference: 1.0 }]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the SVG data is invalid.
+    pub fn parse_verbose<T: AsRef<str>>(
+        input: T,
+    ) -> Result<(Metadata, Vec<Warning>), MetadataError> {
+        /// Maximum difference between the aspect ratios
+        const ASPECT_RATIO_TOLERANCE: f64 = 0.01;
+
+        let doc = parse_document(input.as_ref())?;
+        let root = doc.root_element();
+        let meta = Self::from_element(root, ParseOptions::default());
+
+        let mut warnings = Vec::new();
+        let mut check = |name: &'static str, result: Result<(), MetadataError>| {
+            if let Err(e) = result {
+                warnings.push(Warning::InvalidAttribute {
+                    name,
+                    message: e.to_string(),
+                });
+            }
+        };
+        if let Some(val) = root.attribute("viewBox") {
+            check("viewBox", ViewBox::try_from(val).map(|_| ()));
+        }
+        if let Some(val) = root.attribute("width") {
+            check("width", Dimension::try_from(val).map(|_| ()));
+        }
+        if let Some(val) = root.attribute("height") {
+            check("height", Dimension::try_from(val).map(|_| ()));
+        }
+
+        let distorts = root
+            .attribute("preserveAspectRatio")
+            .and_then(|val| PreserveAspectRatio::try_from(val).ok())
+            .is_some_and(|preserve| preserve.align == Align::None);
+        if !distorts {
+            if let Some(difference) = meta.aspect_ratio_mismatch(ASPECT_RATIO_TOLERANCE) {
+                warnings.push(Warning::AspectRatioMismatch { difference });
+            }
+        }
+        Ok((meta, warnings))
+    }
+
+    /// Parse an XML or XHTML document and extract metadata from the first
+    /// `<svg>` element in it, e.g. an SVG which is inlined in a HTML page.
+    ///
+    /// Returns `None` if the document doesn't contain an `<svg>` element.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::{Metadata, ViewBox};
+    ///
+    /// let html = r#"<html><body>
+    ///   <svg viewBox="0 0 24 24"><path d="M0 0h24v24H0z"/></svg>
+    /// </body></html>"#;
+    ///
+    /// let meta = Metadata::parse_embedded(html).unwrap().unwrap();
+    /// assert_eq!(meta.view_box, Some(ViewBox::try_from("0 0 24 24").unwrap()));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document is not well-formed XML.
+    pub fn parse_embedded<T: AsRef<str>>(input: T) -> Result<Option<Metadata>, MetadataError> {
+        let doc = parse_document(input.as_ref())?;
+        let svg_elem = doc
+            .descendants()
+            .find(|node| node.is_element() && node.tag_name().name() == "svg");
+        Ok(svg_elem.map(|elem| Self::from_element(elem, ParseOptions::default())))
+    }
+
+    /// Check the attributes of an `<svg>` element which are rejected in
+    /// strict mode, see [`ParseOptions::strict`]
+    fn check_strict(
+        svg_elem: roxmltree::Node<'_, '_>,
+        options: ParseOptions,
+    ) -> Result<(), MetadataError> {
+        if let Some(Err(e)) = svg_elem
+            .attribute("viewBox")
+            .map(|val| parse_view_box(val, options))
+        {
+            return Err(invalid_attribute("viewBox", &e));
+        }
+        for name in ["width", "height"] {
+            let Some(val) = svg_elem.attribute(name) else {
+                continue;
+            };
+            if has_padded_unit(val) {
+                let e = MetadataError::new(&format!("Whitespace before unit: {val}"));
+                return Err(invalid_attribute(name, &e));
+            }
+            if let Err(e) = Dimension::try_from(val) {
+                return Err(invalid_attribute(name, &e));
+            }
+        }
+        Ok(())
+    }
+
+    /// Extract metadata from the attributes of an `<svg>` element.
+    /// Invalid attributes are ignored; strict mode is handled by
+    /// [`Metadata::check_strict`].
+    fn from_element(svg_elem: roxmltree::Node<'_, '_>, options: ParseOptions) -> Metadata {
+        let (view_box, recovered) = match svg_elem
+            .attribute("viewBox")
+            .map(|val| parse_view_box(val, options))
+        {
+            Some(Ok((view_box, recovered))) => (Some(view_box), recovered),
+            _ => (None, false),
+        };
+
+        let dimension = |name| {
+            svg_elem
+                .attribute(name)
+                .and_then(|val| Dimension::try_from(val).ok())
+        };
+        let width_dimension = dimension("width");
+        let width = match width_dimension {
+            Some(Dimension::Length(width, unit)) => Some(Width { width, unit }),
+            #[allow(deprecated)]
+            Some(Dimension::Percentage(width)) => Some(Width {
+                width,
+                unit: Unit::Percent,
+            }),
+            _ => None,
+        };
+
+        let height_dimension = dimension("height");
+        let height = match height_dimension {
+            Some(Dimension::Length(height, unit)) => Some(Height { height, unit }),
+            #[allow(deprecated)]
+            Some(Dimension::Percentage(height)) => Some(Height {
+                height,
+                unit: Unit::Percent,
+            }),
+            _ => None,
+        };
+
+        let descendants = Descendants::from_element(svg_elem);
+        Metadata {
+            view_box,
+            width,
+            height,
+            fill: svg_elem.attribute("fill").map(ToString::to_string),
+            color: svg_elem.attribute("color").map(ToString::to_string),
+            overflow: svg_elem.attribute("overflow").map(ToString::to_string),
+            clip: svg_elem.attribute("clip").map(ToString::to_string),
+            required_extensions: split_list(svg_elem.attribute("requiredExtensions")),
+            required_features: split_list(svg_elem.attribute("requiredFeatures")),
+            root_tag: svg_elem.tag_name().name().to_string(),
+            width_dimension,
+            height_dimension,
+            unitless: Unitless::from_element(svg_elem),
+            recovered,
+            attribute_ranges: AttributeRanges::from_element(svg_elem),
+            defined_ids: descendants.defined_ids,
+            font_size: svg_elem.attribute("font-size").and_then(parse_font_size),
+            raw_view_box: svg_elem.attribute("viewBox").map(ToString::to_string),
+            raw_width: svg_elem.attribute("width").map(ToString::to_string),
+            raw_height: svg_elem.attribute("height").map(ToString::to_string),
+            images: descendants.images,
+            use_refs: descendants.use_refs,
+            has_raster: descendants.has_raster,
+            declared_aspect_ratio: svg_elem
+                .attribute("aspect-ratio")
+                .and_then(parse_aspect_ratio),
+            namespaces: svg_elem
+                .namespaces()
+                // The `xml` prefix is bound implicitly
+                .filter(|ns| ns.name() != Some("xml"))
+                .map(|ns| {
+                    (
+                        ns.name().unwrap_or_default().to_string(),
+                        ns.uri().to_string(),
+                    )
+                })
+                .collect(),
+            attributes: attribute_map(svg_elem),
+            classes: split_list(svg_elem.attribute("class")),
+            base_url: None,
+        }
+    }
+
+    /// Returns the value of the `width` attribute.
+    /// If the width is set to 100% then this refers to
+    /// the width of the viewbox.
+    /// A percentage width without a viewBox can't be resolved
+    /// and returns `None`.
+    #[must_use]
+    pub fn width(&self) -> Option<f64> {
+        let w = self.width?;
+        match Dimension::from_length(w.width, w.unit) {
+            Dimension::Percentage(percent) => self.view_box.map(|v| percent / 100.0 * v.width),
+            _ => Some(w.width),
+        }
+    }
+
+    /// Returns the value of the `height` attribute.
+    /// If the height is set to 100% then this refers to
+    /// the height of the viewbox.
+    /// A percentage height without a viewBox can't be resolved
+    /// and returns `None`.
+    #[must_use]
+    pub fn height(&self) -> Option<f64> {
+        let h = self.height?;
+        match Dimension::from_length(h.height, h.unit) {
+            Dimension::Percentage(percent) => self.view_box.map(|v| percent / 100.0 * v.height),
+            _ => Some(h.height),
+        }
+    }
+
+    /// Returns [`Metadata::width`], falling back to the width of the
+    /// viewBox and finally to `default`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let meta = Metadata::parse(r#"<svg viewBox="0 0 24 12"/>"#).unwrap();
+    /// assert_eq!(meta.width_or(100.0), 24.0);
+    /// ```
+    #[must_use]
+    pub fn width_or(&self, default: f64) -> f64 {
+        self.width()
+            .or_else(|| self.view_box.map(|v| v.width))
+            .unwrap_or(default)
+    }
+
+    /// Returns [`Metadata::height`], falling back to the height of the
+    /// viewBox and finally to `default`.
+    #[must_use]
+    pub fn height_or(&self, default: f64) -> f64 {
+        self.height()
+            .or_else(|| self.view_box.map(|v| v.height))
+            .unwrap_or(default)
+    }
+
+    /// Return `view_box`
+    #[must_use]
+    pub const fn view_box(&self) -> Option<ViewBox> {
+        self.view_box
+    }
+
+    /// Returns which of the `view_box`, `width`, and `height` fields are set.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let meta = Metadata::parse(r#"<svg viewBox="0 0 10 10" width="10"/>"#).unwrap();
+    /// let presence = meta.presence();
+    /// assert!(presence.has_view_box && presence.has_width && !presence.has_height);
+    /// assert_eq!(presence.count(), 2);
+    /// ```
+    #[must_use]
+    pub const fn presence(&self) -> FieldPresence {
+        FieldPresence {
+            has_view_box: self.view_box.is_some(),
+            has_width: self.width.is_some(),
+            has_height: self.height.is_some(),
+        }
+    }
+
+    /// Returns the physical size of the image in millimeters
+    /// when printed at the given DPI.
+    ///
+    /// Lengths without a unit are in pixels. Missing dimensions fall back
+    /// to the size of the viewBox, which is interpreted in pixels.
+    /// Returns `None` if a dimension can't be resolved, e.g. because it is
+    /// font-relative or a percentage without a viewBox. The result is not rounded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let meta = Metadata::parse(r#"<svg width="96px" height="1in"/>"#).unwrap();
+    /// let (width, height) = meta.physical_size_mm(96.0).unwrap();
+    /// assert!((width - 25.4).abs() < 1e-9);
+    /// assert!((height - 25.4).abs() < 1e-9);
+    /// ```
+    #[must_use]
+    pub fn physical_size_mm(&self, dpi: f64) -> Option<(f64, f64)> {
+        let options = ResolveOptions {
+            dpi,
+            ..ResolveOptions::default()
+        };
+        let width = self.resolve_width(&options)?;
+        let height = self.resolve_height(&options)?;
+        Some((width / dpi * 25.4, height / dpi * 25.4))
+    }
+
+    /// Returns the size in whole pixels at which to rasterize the image
+    /// at the given DPI, e.g. for the `image` crate. Each axis is at most
+    /// `max` pixels.
+    ///
+    /// Dimensions are resolved like in [`Metadata::resolve_width`] with
+    /// the default [`ResolveOptions`] and then scaled from the CSS
+    /// reference resolution of 96 DPI, so a `24px` icon is rasterized at
+    /// 48x48 pixels at 192 DPI. If the image is larger than `max`, it is
+    /// scaled down to fit, preserving the aspect ratio. The result is
+    /// rounded, but never smaller than one pixel.
+    ///
+    /// Returns `None` if the size can't be resolved or is zero or
+    /// negative, or if `dpi` isn't a positive, finite number or `max` is
+    /// zero.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let meta = Metadata::parse(r#"<svg viewBox="0 0 24 24"/>"#).unwrap();
+    /// assert_eq!(meta.raster_dimensions(192.0, 4096), Some((48, 48)));
+    /// assert_eq!(meta.raster_dimensions(192.0, 32), Some((32, 32)));
+    /// ```
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn raster_dimensions(&self, dpi: f64, max: u32) -> Option<(u32, u32)> {
+        if !(dpi.is_finite() && dpi > 0.0) || max == 0 {
+            return None;
+        }
+        let options = ResolveOptions::default();
+        let scale = dpi / options.dpi;
+        let width = self.resolve_width(&options)? * scale;
+        let height = self.resolve_height(&options)? * scale;
+        if !(width.is_finite() && height.is_finite() && width > 0.0 && height > 0.0) {
+            return None;
+        }
+
+        let max = f64::from(max);
+        let fit = (max / width.max(height)).min(1.0);
+        // In range after clamping, so the casts don't truncate
+        let pixels = |length: f64| (length * fit).round().clamp(1.0, max) as u32;
+        Some((pixels(width), pixels(height)))
+    }
+
+    /// Returns the width and height of the image in pixels, or an error
+    /// if they can't be determined.
+    ///
+    /// This is a precondition check for consumers like thumbnailers,
+    /// which can't render an image without a size. Dimensions are
+    /// resolved with the default [`ResolveOptions`], see
+    /// [`Metadata::resolve_width`]. If an attribute is present but can't
+    /// be resolved, e.g. because it is font-relative, the size of the
+    /// viewBox is used instead.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::{ErrorKind, Metadata};
+    ///
+    /// let meta = Metadata::parse(r#"<svg viewBox="0 0 96 105"/>"#).unwrap();
+    /// assert_eq!(meta.require_dimensions().unwrap(), (96.0, 105.0));
+    ///
+    /// let meta = Metadata::parse("<svg/>").unwrap();
+    /// let err = meta.require_dimensions().unwrap_err();
+    /// assert_eq!(err.kind(), ErrorKind::NoIntrinsicSize);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind [`ErrorKind::NoIntrinsicSize`] if the width
+    /// or height can't be resolved to pixels.
+    pub fn require_dimensions(&self) -> Result<(f64, f64), MetadataError> {
+        let options = ResolveOptions::default();
+        let view_box = self.view_box;
+        let width = self
+            .resolve_width(&options)
+            .or_else(|| view_box.map(|v| v.width));
+        let height = self
+            .resolve_height(&options)
+            .or_else(|| view_box.map(|v| v.height));
+        let (Some(width), Some(height)) = (width, height) else {
+            let missing = match (width, height) {
+                (None, None) => "width and height",
+                (None, _) => "width",
+                _ => "height",
+            };
+            return Err(MetadataError::with_kind(
+                ErrorKind::NoIntrinsicSize,
+                &format!(
+                    "Cannot determine the {missing} of the image: \
+                     expected absolute dimensions or a viewBox"
+                ),
+            ));
+        };
+        Ok((width, height))
+    }
+
+    /// Returns the options used to resolve the dimensions of the root
+    /// element. The font size is taken from `options` if set, otherwise
+    /// from the root `font-size` attribute, otherwise from the CSS default
+    /// if enabled.
+    fn root_resolve_options(&self, options: &ResolveOptions) -> ResolveOptions {
+        let default_font_size = options
+            .use_css_default_font_size
+            .then_some(ResolveOptions::CSS_DEFAULT_FONT_SIZE);
+        let font_size = options.font_size.or_else(|| {
+            let Some((size, unit)) = self.font_size else {
+                return default_font_size;
+            };
+            // Font-relative sizes refer to the parent font, which is
+            // unknown unless the CSS default is used
+            let parent = ResolveOptions {
+                font_size: default_font_size,
+                ..*options
+            };
+            parent.length_px(size, unit)
+        });
+        ResolveOptions {
+            font_size,
+            ..*options
+        }
+    }
+
+    /// Resolve the width of the image to pixels.
+    ///
+    /// Percentages refer to the width of the viewBox (see [`Metadata::width`]).
+    /// Font-relative units use the font size from `options` if set,
+    /// otherwise the `font-size` attribute of the root element, otherwise
+    /// 16px if [`ResolveOptions::use_css_default_font_size`] is set.
+    /// If none is available, they can't be resolved.
+    /// Lengths without a unit are in pixels, regardless of the DPI.
+    /// If there is no `width` attribute, the width of the viewBox is used.
+    /// This can be changed with [`ResolveOptions::dimension_source`].
+    /// The result is only rounded if [`ResolveOptions::round_to_int`] is set.
+    /// Returns `None` if the width can't be resolved.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::{Metadata, ResolveOptions};
+    ///
+    /// let meta = Metadata::parse(r#"<svg width="2em"/>"#).unwrap();
+    /// assert_eq!(meta.resolve_width(&ResolveOptions::default()), None);
+    ///
+    /// let options = ResolveOptions {
+    ///     font_size: Some(16.0),
+    ///     ..ResolveOptions::default()
+    /// };
+    /// assert_eq!(meta.resolve_width(&options), Some(32.0));
+    ///
+    /// let meta = Metadata::parse(r#"<svg width="2em" font-size="20"/>"#).unwrap();
+    /// assert_eq!(meta.resolve_width(&ResolveOptions::default()), Some(40.0));
+    /// assert_eq!(meta.resolve_width(&options), Some(32.0));
+    /// ```
+    #[must_use]
+    pub fn resolve_width(&self, options: &ResolveOptions) -> Option<f64> {
+        let options = self.root_resolve_options(options);
+        let view_box_width = self.view_box.map(|v| v.width);
+        let width = match (options.dimension_source, self.width) {
+            (DimensionSource::Attributes, None) => None,
+            (DimensionSource::ViewBox, _) | (_, None) => view_box_width,
+            (_, Some(w)) if self.unitless.width => Some(w.width),
+            (_, Some(w)) => match Dimension::from_length(w.width, w.unit) {
+                Dimension::Percentage(_) => self.width(),
+                _ => options.length_px(w.width, w.unit),
+            },
+        };
+        width.map(|px| options.round(px))
+    }
+
+    /// Resolve the height of the image to pixels.
+    ///
+    /// Percentages refer to the height of the viewBox (see [`Metadata::height`]).
+    /// Font-relative units are resolved like in [`Metadata::resolve_width`].
+    /// If there is no `height` attribute, the height of the viewBox is used,
+    /// depending on [`ResolveOptions::dimension_source`].
+    /// Returns `None` if the height can't be resolved.
+    #[must_use]
+    pub fn resolve_height(&self, options: &ResolveOptions) -> Option<f64> {
+        let options = self.root_resolve_options(options);
+        let view_box_height = self.view_box.map(|v| v.height);
+        let height = match (options.dimension_source, self.height) {
+            (DimensionSource::Attributes, None) => None,
+            (DimensionSource::ViewBox, _) | (_, None) => view_box_height,
+            (_, Some(h)) if self.unitless.height => Some(h.height),
+            (_, Some(h)) => match Dimension::from_length(h.height, h.unit) {
+                Dimension::Percentage(_) => self.height(),
+                _ => options.length_px(h.height, h.unit),
+            },
+        };
+        height.map(|px| options.round(px))
+    }
+
+    /// Returns the ratio of the declared width to the width of the viewBox,
+    /// i.e. the scale at which the author intended the image to be
+    /// rendered. For example, `width="48px"` on a `viewBox="0 0 24 24"`
+    /// gives a scale of 2.
+    ///
+    /// The width is resolved with the default [`ResolveOptions`].
+    /// Returns `None` if there is no `width` attribute, if it can't be
+    /// resolved, or if there is no viewBox or it has a width of zero.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let meta = Metadata::parse(r#"<svg viewBox="0 0 24 24" width="48px"/>"#).unwrap();
+    /// assert_eq!(meta.width_scale(), Some(2.0));
+    /// ```
+    #[must_use]
+    pub fn width_scale(&self) -> Option<f64> {
+        self.width?;
+        let view_box_width = self.view_box?.width.abs();
+        if view_box_width == 0.0 {
+            return None;
+        }
+        Some(self.resolve_width(&ResolveOptions::default())? / view_box_width)
+    }
+
+    /// Returns the ratio of the declared height to the height of the
+    /// viewBox, like [`Metadata::width_scale`].
+    #[must_use]
+    pub fn height_scale(&self) -> Option<f64> {
+        self.height?;
+        let view_box_height = self.view_box?.height.abs();
+        if view_box_height == 0.0 {
+            return None;
+        }
+        Some(self.resolve_height(&ResolveOptions::default())? / view_box_height)
+    }
+
+    /// Returns a canonical form of the metadata, so that images which
+    /// only differ in how their dimensions are written compare equal.
+    ///
+    /// - The viewBox is normalized (see [`ViewBox::normalized`]).
+    /// - `width` and `height` are converted to pixels at the given DPI
+    ///   where possible. Font-relative units are only converted if the
+    ///   root element has a `font-size`.
+    /// - Attribute ranges and raw attribute values are cleared, as they only
+    ///   describe the input text.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let a = Metadata::parse(r#"<svg viewBox="0 0 10 10" width="1in"/>"#).unwrap();
+    /// let b = Metadata::parse(r#"<svg viewBox="10 10 -10 -10" width="96px"/>"#).unwrap();
+    /// assert_ne!(a, b);
+    /// assert_eq!(a.canonicalize(96.0), b.canonicalize(96.0));
+    /// ```
+    #[must_use]
+    pub fn canonicalize(&self, dpi: f64) -> Metadata {
+        let options = ResolveOptions {
+            dpi,
+            ..ResolveOptions::default()
+        };
+        let mut canonical = self.clone();
+        canonical.view_box = self.view_box.map(|v| v.normalized());
+        if self.width.is_some() {
+            if let Some(width) = self.resolve_width(&options) {
+                canonical.width = Some(Width {
+                    width,
+                    unit: Unit::Px,
+                });
+                canonical.width_dimension = Some(Dimension::Length(width, Unit::Px));
+                canonical.unitless.width = false;
+            }
+        }
+        if self.height.is_some() {
+            if let Some(height) = self.resolve_height(&options) {
+                canonical.height = Some(Height {
+                    height,
+                    unit: Unit::Px,
+                });
+                canonical.height_dimension = Some(Dimension::Length(height, Unit::Px));
+                canonical.unitless.height = false;
+            }
+        }
+        canonical.attribute_ranges = AttributeRanges::default();
+        canonical.raw_view_box = None;
+        canonical.raw_width = None;
+        canonical.raw_height = None;
+        canonical.attributes.clear();
+        canonical
+    }
+
+    /// Returns a hash of the metadata which is stable across runs,
+    /// platforms, and versions of Rust, e.g. to key a cache.
+    ///
+    /// The hash covers the fields compared by [`Metadata::diff`] after
+    /// [`Metadata::canonicalize`] at 96 DPI, so images which only differ in
+    /// how their attributes are written hash equally. Numbers are rounded
+    /// to six decimal places. Information about the input text, such as
+    /// raw attribute values, is not included.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let a = Metadata::parse(r#"<svg viewBox="0,0,96,105" width="1in"/>"#).unwrap();
+    /// let b = Metadata::parse(r#"<svg viewBox="0 0 96 105" width="96px"/>"#).unwrap();
+    /// assert_eq!(a.content_hash(), b.content_hash());
+    /// ```
+    #[must_use]
+    pub fn content_hash(&self) -> u64 {
+        let canonical = self.canonicalize(96.0);
+        let mut hasher = StableHasher::default();
+        hasher.write_str(&canonical.root_tag);
+        hasher.write_option(canonical.view_box.as_ref(), |hasher, view_box| {
+            for value in [
+                view_box.min_x,
+                view_box.min_y,
+                view_box.width,
+                view_box.height,
+            ] {
+                hasher.write_f64(value);
+            }
+        });
+        for dimension in [&canonical.width_dimension, &canonical.height_dimension] {
+            hasher.write_option(dimension.as_ref(), |hasher, dimension| match dimension {
+                Dimension::Length(value, unit) => {
+                    hasher.write_f64(*value);
+                    hasher.write_str(&unit.to_string());
+                }
+                Dimension::Percentage(value) => {
+                    hasher.write_f64(*value);
+                    hasher.write_str("%");
+                }
+                Dimension::Calc(expression) => hasher.write_str(expression),
+            });
+        }
+        for value in [
+            &canonical.fill,
+            &canonical.color,
+            &canonical.overflow,
+            &canonical.clip,
+        ] {
+            hasher.write_option(value.as_ref(), |hasher, value| hasher.write_str(value));
+        }
+        for list in [&canonical.required_extensions, &canonical.required_features] {
+            hasher.write_u64(list.len() as u64);
+            for item in list {
+                hasher.write_str(item);
+            }
+        }
+        hasher.0
+    }
+
+    /// Compare two `Metadata` values field by field.
+    ///
+    /// Returns the fields which differ, with their values formatted
+    /// as they would appear in an SVG file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::{FieldDiff, Metadata};
+    ///
+    /// let left = Metadata::parse(r#"<svg viewBox="0 0 10 10" width="10px"/>"#).unwrap();
+    /// let right = Metadata::parse(r#"<svg viewBox="0 0 10 10" width="20px"/>"#).unwrap();
+    /// assert_eq!(
+    ///     left.diff(&right),
+    ///     vec![FieldDiff {
+    ///         field: "width",
+    ///         left: Some("10px".to_string()),
+    ///         right: Some("20px".to_string()),
+    ///     }]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn diff(&self, other: &Metadata) -> Vec<FieldDiff> {
+        fn to_string<T: ToString>(value: Option<&T>) -> Option<String> {
+            value.map(ToString::to_string)
+        }
+        fn join(values: &[String]) -> Option<String> {
+            (!values.is_empty()).then(|| values.join(" "))
+        }
+
+        let fields = [
+            (
+                "view_box",
+                to_string(self.view_box.as_ref()),
+                to_string(other.view_box.as_ref()),
+            ),
+            (
+                "width",
+                to_string(self.width.as_ref()),
+                to_string(other.width.as_ref()),
+            ),
+            (
+                "height",
+                to_string(self.height.as_ref()),
+                to_string(other.height.as_ref()),
+            ),
+            ("fill", self.fill.clone(), other.fill.clone()),
+            ("color", self.color.clone(), other.color.clone()),
+            ("overflow", self.overflow.clone(), other.overflow.clone()),
+            ("clip", self.clip.clone(), other.clip.clone()),
+            (
+                "required_extensions",
+                join(&self.required_extensions),
+                join(&other.required_extensions),
+            ),
+            (
+                "required_features",
+                join(&self.required_features),
+                join(&other.required_features),
+            ),
+            (
+                "root_tag",
+                Some(self.root_tag.clone()),
+                Some(other.root_tag.clone()),
+            ),
+        ];
+        fields
+            .into_iter()
+            .filter(|(_, left, right)| left != right)
+            .map(|(field, left, right)| FieldDiff { field, left, right })
+            .collect()
+    }
+
+    /// Returns the aspect ratio declared in the `aspect-ratio` attribute
+    /// of the root element, e.g. `16 / 9` or `1.777`.
+    ///
+    /// Returns `None` if there is no such attribute or its value isn't
+    /// a positive, finite ratio.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let meta = Metadata::parse(r#"<svg aspect-ratio="4 / 2"/>"#).unwrap();
+    /// assert_eq!(meta.declared_aspect_ratio(), Some(2.0));
+    /// ```
+    #[must_use]
+    pub const fn declared_aspect_ratio(&self) -> Option<f64> {
+        self.declared_aspect_ratio
+    }
+
+    /// Returns the intrinsic aspect ratio (width / height) of the image.
+    ///
+    /// The ratio is determined by the first of these which yields a
+    /// positive, finite ratio:
+    ///
+    /// 1. The declared `aspect-ratio` (see [`Metadata::declared_aspect_ratio`])
+    /// 2. `width` and `height`, if both are set in the same unit or in
+    ///    absolute units (e.g. `cm` and `in`)
+    /// 3. The viewBox
+    ///
+    /// Returns `None` if none of them does.
+    #[must_use]
+    pub fn intrinsic_aspect_ratio(&self) -> Option<f64> {
+        self.declared_aspect_ratio
+            .or_else(|| self.dimensions_aspect_ratio())
+            .or_else(|| self.view_box_aspect_ratio())
+    }
+
+    /// Returns the difference between the aspect ratio of `width` and
+    /// `height` and the aspect ratio of the viewBox if it exceeds
+    /// `tolerance`.
+    ///
+    /// A mismatch means that the image gets distorted or letterboxed
+    /// (depending on `preserveAspectRatio`), which is often an authoring
+    /// error. Returns `None` if the ratios match or either of them can't
+    /// be determined.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let svg = r#"<svg viewBox="0 0 100 100" width="200" height="100"/>"#;
+    /// let meta = Metadata::parse(svg).unwrap();
+    /// assert_eq!(meta.aspect_ratio_mismatch(0.01), Some(1.0));
+    /// ```
+    #[must_use]
+    pub fn aspect_ratio_mismatch(&self, tolerance: f64) -> Option<f64> {
+        let difference = (self.dimensions_aspect_ratio()? - self.view_box_aspect_ratio()?).abs();
+        (difference > tolerance).then_some(difference)
+    }
+
+    /// Check the image for structural problems, e.g. for a linter.
+    ///
+    /// These checks are run, identified by the code of the issue:
+    ///
+    /// - `non-svg-root` (error): the root element isn't `<svg>`
+    /// - `missing-view-box` (warning): there is no viewBox, so the image
+    ///   can't be scaled
+    /// - `view-box-has-units` (error): the numbers of the viewBox have units
+    /// - `invalid-view-box` (error): the viewBox can't be parsed otherwise
+    /// - `negative-view-box` (error): the viewBox has a negative extent,
+    ///   which disables rendering
+    /// - `invalid-width` and `invalid-height` (error): the attribute can't
+    ///   be parsed
+    /// - `percentage-without-view-box` (warning): a percentage dimension
+    ///   can't be resolved without a viewBox
+    /// - `aspect-ratio-mismatch` (warning): the aspect ratios of the
+    ///   dimensions and the viewBox differ by more than 0.01, unless
+    ///   `preserveAspectRatio="none"` makes the distortion intentional
+    ///
+    /// The checks use the raw attribute values, so they find nothing
+    /// after [`Metadata::canonicalize`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let meta = Metadata::parse(r#"<svg width="100%"/>"#).unwrap();
+    /// let codes: Vec<_> = meta.lint().iter().map(|issue| issue.code).collect();
+    /// assert_eq!(codes, ["missing-view-box", "percentage-without-view-box"]);
+    /// ```
+    #[must_use]
+    pub fn lint(&self) -> Vec<LintIssue> {
+        /// Maximum difference between the aspect ratios
+        const ASPECT_RATIO_TOLERANCE: f64 = 0.01;
+
+        let mut issues = Vec::new();
+        if self.root_tag != "svg" {
+            issues.push(LintIssue::new(
+                Severity::Error,
+                "non-svg-root",
+                format!(
+                    "The root element is `<{}>` instead of `<svg>`",
+                    self.root_tag
+                ),
+            ));
+        }
+
+        match self.raw_view_box.as_deref().map(ViewBox::try_from) {
+            None => issues.push(LintIssue::new(
+                Severity::Warning,
+                "missing-view-box",
+                "There is no viewBox, so the image can't be scaled",
+            )),
+            Some(Err(e)) if e.kind() == ErrorKind::ViewBoxHasUnits => issues.push(LintIssue::new(
+                Severity::Error,
+                "view-box-has-units",
+                e.to_string(),
+            )),
+            Some(Err(e)) => issues.push(LintIssue::new(
+                Severity::Error,
+                "invalid-view-box",
+                e.to_string(),
+            )),
+            Some(Ok(_)) => {}
+        }
+        if let Some(view_box) = self.view_box {
+            if view_box.width < 0.0 || view_box.height < 0.0 {
+                issues.push(LintIssue::new(
+                    Severity::Error,
+                    "negative-view-box",
+                    format!("The viewBox `{view_box}` has a negative extent"),
+                ));
+            }
+        }
+
+        for (code, raw, dimension) in [
+            ("invalid-width", &self.raw_width, &self.width_dimension),
+            ("invalid-height", &self.raw_height, &self.height_dimension),
+        ] {
+            if let (Some(raw), None) = (raw, dimension) {
+                issues.push(LintIssue::new(
+                    Severity::Error,
+                    code,
+                    format!("Cannot parse `{raw}`"),
+                ));
+            }
+        }
+
+        let percent = [&self.width_dimension, &self.height_dimension]
+            .iter()
+            .any(|dimension| matches!(dimension, Some(Dimension::Percentage(_))));
+        if percent && self.view_box.is_none() {
+            issues.push(LintIssue::new(
+                Severity::Warning,
+                "percentage-without-view-box",
+                "Percentages can't be resolved without a viewBox",
+            ));
+        }
+
+        let distorts = self
+            .attributes
+            .get("preserveAspectRatio")
+            .and_then(|val| PreserveAspectRatio::try_from(val.as_str()).ok())
+            .is_some_and(|preserve| preserve.align == Align::None);
+        if !distorts {
+            if let Some(difference) = self.aspect_ratio_mismatch(ASPECT_RATIO_TOLERANCE) {
+                issues.push(LintIssue::new(
+                    Severity::Warning,
+                    "aspect-ratio-mismatch",
+                    Warning::AspectRatioMismatch { difference }.to_string(),
+                ));
+            }
+        }
+        issues
+    }
+
+    /// The aspect ratio of `width` and `height` if they are comparable
+    fn dimensions_aspect_ratio(&self) -> Option<f64> {
+        let (w, h) = (self.width?, self.height?);
+        let width = Dimension::from_length(w.width, w.unit);
+        let height = Dimension::from_length(h.height, h.unit);
+        let (Dimension::Length(width, width_unit), Dimension::Length(height, height_unit)) =
+            (width, height)
+        else {
+            // Percentages refer to the viewBox and aren't comparable
+            return None;
+        };
+        let ratio = if width_unit == height_unit {
+            width / height
+        } else {
+            // The DPI cancels out, so any value works here
+            let dpi = 96.0;
+            (width * width_unit.scale_factor(dpi)?) / (height * height_unit.scale_factor(dpi)?)
+        };
+        positive_ratio(ratio)
+    }
+
+    /// The aspect ratio of the viewBox
+    fn view_box_aspect_ratio(&self) -> Option<f64> {
+        self.view_box
+            .and_then(|v| positive_ratio(v.width / v.height))
+    }
+
+    /// Returns the intrinsic aspect ratio as a simplified integer ratio,
+    /// e.g. `(16, 9)` for a 1920×1080 image.
+    ///
+    /// The ratio is approximated with continued fractions, returning the
+    /// first (i.e. smallest) fraction that is within 0.5% of the actual
+    /// aspect ratio. That way dimensions which are slightly off, like
+    /// 1366×768, still map to `(16, 9)`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let meta = Metadata::parse(r#"<svg viewBox="0 0 1920 1080"/>"#).unwrap();
+    /// assert_eq!(meta.aspect_ratio_simplified(), Some((16, 9)));
+    /// ```
+    #[must_use]
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    pub fn aspect_ratio_simplified(&self) -> Option<(u64, u64)> {
+        /// Maximum relative deviation from the actual aspect ratio
+        const TOLERANCE: f64 = 0.005;
+
+        let ratio = self.intrinsic_aspect_ratio()?;
+
+        // Numerators and denominators of the previous two convergents
+        let (mut num, mut prev_num) = (1_u64, 0_u64);
+        let (mut den, mut prev_den) = (0_u64, 1_u64);
+        let mut x = ratio;
+        loop {
+            let a = x.floor();
+            if a > u32::MAX.into() {
+                return None;
+            }
+            let a = a as u64;
+            (num, prev_num) = (a * num + prev_num, num);
+            (den, prev_den) = (a * den + prev_den, den);
+
+            if den > 0 && ((num as f64 / den as f64) - ratio).abs() <= ratio * TOLERANCE {
+                return Some((num, den));
+            }
+            x = 1.0 / (x - x.floor());
+        }
+    }
+
+    /// Returns the orientation of the image, derived from the intrinsic
+    /// aspect ratio (see [`Metadata::intrinsic_aspect_ratio`]).
+    ///
+    /// Images whose aspect ratio deviates from 1 by at most 1% count as
+    /// square, to absorb rounding in exported sizes. Returns `None` if the
+    /// aspect ratio can't be determined.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::{Metadata, Orientation};
+    ///
+    /// let meta = Metadata::parse(r#"<svg viewBox="0 0 1920 1080"/>"#).unwrap();
+    /// assert_eq!(meta.orientation(), Some(Orientation::Landscape));
+    /// ```
+    #[must_use]
+    pub fn orientation(&self) -> Option<Orientation> {
+        /// Maximum relative deviation from a square aspect ratio
+        const TOLERANCE: f64 = 0.01;
+
+        let ratio = self.intrinsic_aspect_ratio()?;
+        if (ratio - 1.0).abs() <= TOLERANCE {
+            Some(Orientation::Square)
+        } else if ratio > 1.0 {
+            Some(Orientation::Landscape)
+        } else {
+            Some(Orientation::Portrait)
+        }
+    }
+
+    /// Returns `true` if the image is square, see [`Metadata::orientation`]
+    #[must_use]
+    pub fn is_square(&self) -> bool {
+        self.orientation() == Some(Orientation::Square)
+    }
+
+    /// Returns `true` if the image is wider than it is tall,
+    /// see [`Metadata::orientation`]
+    #[must_use]
+    pub fn is_landscape(&self) -> bool {
+        self.orientation() == Some(Orientation::Landscape)
+    }
+
+    /// Returns `true` if the image is taller than it is wide,
+    /// see [`Metadata::orientation`]
+    #[must_use]
+    pub fn is_portrait(&self) -> bool {
+        self.orientation() == Some(Orientation::Portrait)
+    }
+
+    /// Returns the `width` attribute as a `Dimension`.
+    ///
+    /// Unlike the `width` field this also covers `calc()` expressions,
+    /// which would otherwise be indistinguishable from a missing width,
+    /// and it keeps percentages apart from lengths.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::{Dimension, Metadata};
+    ///
+    /// let meta = Metadata::parse(r#"<svg width="calc(100% - 20px)"/>"#).unwrap();
+    /// assert_eq!(meta.width, None);
+    /// assert_eq!(
+    ///     meta.width_dimension(),
+    ///     Some(&Dimension::Calc("calc(100% - 20px)".to_string()))
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn width_dimension(&self) -> Option<&Dimension> {
+        self.width_dimension.as_ref()
+    }
+
+    /// Returns the `height` attribute as a `Dimension`.
+    ///
+    /// Unlike the `height` field this also covers `calc()` expressions,
+    /// and it keeps percentages apart from lengths.
+    #[must_use]
+    pub const fn height_dimension(&self) -> Option<&Dimension> {
+        self.height_dimension.as_ref()
+    }
+
+    /// Returns `true` if any attribute was recovered from a malformed value.
+    /// This can only happen if [`ParseOptions::recover`] is set.
+    #[must_use]
+    pub const fn recovered(&self) -> bool {
+        self.recovered
+    }
+
+    /// Returns the `viewBox` attribute as written, even if it couldn't
+    /// be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let meta = Metadata::parse(r#"<svg viewBox="0 0 96px 105px"/>"#).unwrap();
+    /// assert_eq!(meta.view_box, None);
+    /// assert_eq!(meta.raw_view_box(), Some("0 0 96px 105px"));
+    /// ```
+    #[must_use]
+    pub fn raw_view_box(&self) -> Option<&str> {
+        self.raw_view_box.as_deref()
+    }
+
+    /// Returns the `width` attribute as written, even if it couldn't
+    /// be parsed.
+    #[must_use]
+    pub fn raw_width(&self) -> Option<&str> {
+        self.raw_width.as_deref()
+    }
+
+    /// Returns the `height` attribute as written, even if it couldn't
+    /// be parsed.
+    #[must_use]
+    pub fn raw_height(&self) -> Option<&str> {
+        self.raw_height.as_deref()
+    }
+
+    /// Returns all attributes of the root element by their qualified name
+    /// (e.g. `xlink:href`), with their values as written.
+    ///
+    /// This includes attributes without a dedicated accessor, such as
+    /// `zoomAndPan`, `contentScriptType`, or `contentStyleType`, which is
+    /// useful to archive the metadata faithfully. Namespace declarations
+    /// are available through [`Metadata::namespaces`] instead.
+    ///
+    /// Names of common attributes are shared between all parsed images
+    /// to save memory, so only uncommon names are owned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let meta = Metadata::parse(r#"<svg zoomAndPan="disable"/>"#).unwrap();
+    /// assert_eq!(meta.attributes()["zoomAndPan"], "disable");
+    /// ```
+    #[must_use]
+    pub const fn attributes(&self) -> &BTreeMap<Cow<'static, str>, String> {
+        &self.attributes
+    }
+
+    /// Returns the `data-*` attributes of the root element, e.g. for
+    /// application-specific information, with the `data-` prefix removed
+    /// from their names.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let meta = Metadata::parse(r#"<svg data-icon-name="home" id="icon"/>"#).unwrap();
+    /// assert_eq!(meta.data_attributes()["icon-name"], "home");
+    /// assert_eq!(meta.data_attributes().len(), 1);
+    /// ```
+    #[must_use]
+    pub fn data_attributes(&self) -> BTreeMap<String, String> {
+        self.attributes
+            .iter()
+            .filter_map(|(name, value)| {
+                let name = name.strip_prefix("data-")?;
+                Some((name.to_string(), value.clone()))
+            })
+            .collect()
+    }
+
+    /// Returns the byte ranges of the `viewBox`, `width`, and `height`
+    /// attributes in the parsed input.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let svg = r#"<svg width="10px"/>"#;
+    /// let meta = Metadata::parse(svg).unwrap();
+    /// let range = meta.attribute_ranges().width.clone().unwrap();
+    /// assert_eq!(&svg[range], r#"width="10px""#);
+    /// ```
+    #[must_use]
+    pub const fn attribute_ranges(&self) -> &AttributeRanges {
+        &self.attribute_ranges
+    }
+
+    /// Returns the `id`s of all elements with the given tag name,
+    /// e.g. `linearGradient` or `filter`, in document order.
+    ///
+    /// Only descendants of the `<svg>` element are considered.
+    /// Elements without an `id` are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let svg = r#"<svg><defs><filter id="blur"/><filter/></defs></svg>"#;
+    /// let meta = Metadata::parse(svg).unwrap();
+    /// assert_eq!(meta.defined_ids_by_tag("filter"), vec!["blur"]);
+    /// ```
+    #[must_use]
+    pub fn defined_ids_by_tag(&self, tag: &str) -> Vec<String> {
+        self.defined_ids
+            .iter()
+            .filter(|(name, _)| name == tag)
+            .map(|(_, id)| id.clone())
+            .collect()
+    }
+
+    /// Returns the references of all `<image>` elements, e.g. file names
+    /// or data URIs, in document order.
+    ///
+    /// Both `href` and the deprecated `xlink:href` are read. If an element
+    /// has both, `href` takes precedence as in SVG 2.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let svg = r#"<svg xmlns:xlink="http://www.w3.org/1999/xlink">
+    ///   <image href="a.png"/>
+    ///   <image xlink:href="b.png"/>
+    /// </svg>"#;
+    /// let meta = Metadata::parse(svg).unwrap();
+    /// assert_eq!(meta.images(), ["a.png", "b.png"]);
+    /// ```
+    #[must_use]
+    pub fn images(&self) -> &[String] {
+        &self.images
+    }
+
+    /// Returns the references of all `<use>` elements in document order,
+    /// e.g. to build a dependency graph between symbols.
+    ///
+    /// References to elements in the same document are returned as their
+    /// id, without the leading `#`. References to other files, like
+    /// `sprite.svg#icon`, are returned as written. `href` and `xlink:href`
+    /// are read like in [`Metadata::images`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let svg = r##"<svg><use href="#star"/><use href="sprite.svg#icon"/></svg>"##;
+    /// let meta = Metadata::parse(svg).unwrap();
+    /// assert_eq!(meta.use_refs(), ["star", "sprite.svg#icon"]);
+    /// ```
+    #[must_use]
+    pub fn use_refs(&self) -> &[String] {
+        &self.use_refs
+    }
+
+    /// Returns the references of all `<image>` elements like
+    /// [`Metadata::images`], together with their resolved form if a base
+    /// URL was passed to [`Metadata::parse_with_base_url`].
+    ///
+    /// Absolute references, including `data:` URIs, are not changed.
+    ///
+    /// Requires the `url` feature.
+    #[cfg(feature = "url")]
+    #[must_use]
+    pub fn image_refs(&self) -> Vec<ImageRef> {
+        let base_url = self
+            .base_url
+            .as_deref()
+            .and_then(|base| url::Url::parse(base).ok());
+        self.images
+            .iter()
+            .map(|href| {
+                let resolved = if url::Url::parse(href).is_ok() {
+                    Some(href.clone())
+                } else {
+                    base_url
+                        .as_ref()
+                        .and_then(|base| base.join(href).ok())
+                        .map(String::from)
+                };
+                ImageRef {
+                    href: href.clone(),
+                    resolved,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the class names in the `class` attribute of the root
+    /// element, e.g. for CSS-based theming. Empty if there is no such
+    /// attribute.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let meta = Metadata::parse(r#"<svg class="icon dark"/>"#).unwrap();
+    /// assert_eq!(meta.classes(), ["icon", "dark"]);
+    /// ```
+    #[must_use]
+    pub fn classes(&self) -> &[String] {
+        &self.classes
+    }
+
+    /// Returns the `fill` attribute of the root element as a color.
+    ///
+    /// Requires the `color` feature. Returns `None` if there is no `fill`
+    /// or it isn't a plain color (see [`Color::parse`]). The attribute
+    /// as written is available in [`Metadata::fill`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::{Color, Metadata};
+    ///
+    /// let meta = Metadata::parse(r##"<svg fill="#ff0000"/>"##).unwrap();
+    /// assert_eq!(meta.fill_color(), Some(Color::rgb(255, 0, 0)));
+    /// ```
+    #[cfg(feature = "color")]
+    #[must_use]
+    pub fn fill_color(&self) -> Option<Color> {
+        Color::parse(self.fill.as_deref()?)
+    }
+
+    /// Returns the namespaces declared on the root element (or on its
+    /// ancestors for embedded images), mapping each prefix to its URI.
+    /// The default namespace has an empty prefix.
+    ///
+    /// Useful to detect editor-specific extensions, e.g. from Inkscape.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let svg = r#"<svg xmlns="http://www.w3.org/2000/svg"
+    ///     xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape"/>"#;
+    /// let meta = Metadata::parse(svg).unwrap();
+    /// assert_eq!(meta.namespaces()[""], "http://www.w3.org/2000/svg");
+    /// assert!(meta.namespaces().contains_key("inkscape"));
+    /// ```
+    #[must_use]
+    pub const fn namespaces(&self) -> &BTreeMap<String, String> {
+        &self.namespaces
+    }
+
+    /// Returns `true` if the image contains any `<image>` elements,
+    /// i.e. it isn't pure vector graphics.
+    ///
+    /// Unlike [`Metadata::images`], this includes elements without a
+    /// reference. Note that an `<image>` may also embed another SVG image.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let meta = Metadata::parse(r#"<svg><image href="photo.jpg"/></svg>"#).unwrap();
+    /// assert!(meta.has_raster());
+    /// ```
+    #[must_use]
+    pub const fn has_raster(&self) -> bool {
+        self.has_raster
+    }
+
+    /// Returns the local name of the document's root element
+    /// (without any namespace prefix).
+    ///
+    /// `parse` accepts any well-formed XML, so this can be used to check
+    /// that the input actually was an SVG image.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use svg_metadata::Metadata;
+    ///
+    /// let meta = Metadata::parse("<html><body/></html>").unwrap();
+    /// assert_ne!(meta.root_tag(), "svg");
+    /// ```
+    #[must_use]
+    pub fn root_tag(&self) -> &str {
+        &self.root_tag
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_view_box_separators() {
+        // Values can be separated by whitespace and/or a comma
+        let cases = vec![
+            "0 1 99 100",
+            "0, 1, 99, 100",
+            "0, 1  99 100",
+            "0,1,99,100",
+            "0 ,1 , 99,100",
+        ];
+        for case in cases {
+            assert_eq!(
+                ViewBox::try_from(case).unwrap(),
+                ViewBox {
+                    min_x: 0.0,
+                    min_y: 1.0,
+                    width: 99.0,
+                    height: 100.0
+                }
+            );
+        }
+        assert!(ViewBox::try_from("0,,1,99,100").is_err());
+        assert!(ViewBox::try_from("0,1,99,100,").is_err());
+    }
+
+    #[test]
+    fn test_view_box_units() {
+        let err = ViewBox::try_from("0px 0px 96px 105px").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ViewBoxHasUnits);
+
+        let err = ViewBox::try_from("0 0 96 105%").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ViewBoxHasUnits);
+
+        let err = ViewBox::try_from("0 0 96 abc").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Other);
+
+        let svg = r#"<svg viewBox="0px 0px 96px 105px"/>"#;
+        assert_eq!(Metadata::parse(svg).unwrap().view_box, None);
+        let err = Metadata::parse_strict(svg).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ViewBoxHasUnits);
+
+        let options = ParseOptions {
+            recover: true,
+            ..ParseOptions::default()
+        };
+        let meta = Metadata::parse_with_options(svg, options).unwrap();
+        assert_eq!(meta.view_box, Some(ViewBox::from_dimensions(96.0, 105.0)));
+        assert!(meta.recovered());
+    }
+
+    #[test]
+    fn test_view_box_negative() {
+        assert_eq!(
+            ViewBox::try_from("-0, 1, -99.00001, -100.3").unwrap(),
+            ViewBox {
+                min_x: 0.0,
+                min_y: 1.0,
+                width: -99.00001,
+                height: -100.3
+            }
+        );
+    }
+
+    #[test]
+    fn test_view_box_normalized() {
+        let view_box = ViewBox::try_from("10 20 -4 5").unwrap();
+        assert_eq!(
+            view_box.normalized(),
+            ViewBox {
+                min_x: 6.0,
+                min_y: 20.0,
+                width: 4.0,
+                height: 5.0
+            }
+        );
+
+        let view_box = ViewBox::try_from("10 20 4 -5").unwrap();
+        assert_eq!(
+            view_box.normalized(),
+            ViewBox {
+                min_x: 10.0,
+                min_y: 15.0,
+                width: 4.0,
+                height: 5.0
+            }
+        );
+
+        let view_box = ViewBox::try_from("0 0 96 105").unwrap();
+        assert_eq!(view_box.normalized(), view_box);
+    }
+
+    #[test]
+    fn test_view_box_to_int_rect() {
+        let view_box = ViewBox::try_from("0.4 0.6 96.5 105.4").unwrap();
+        // Covers up to 96.9 and 106.0, where rounding gives 97 and 105
+        assert_eq!(view_box.to_int_rect(), (0, 0, 97, 106));
+
+        // Rounding each value on its own would lose coverage here
+        let view_box = ViewBox::try_from("0.6 0 1.8 1").unwrap();
+        assert_eq!(view_box.round().width, 2.0);
+        assert_eq!(view_box.to_int_rect(), (0, 0, 3, 1));
+
+        assert_eq!(
+            ViewBox::try_from("-0.5 -1.5 -2 3").unwrap().to_int_rect(),
+            (-3, -2, 3, 4)
+        );
+        assert_eq!(
+            ViewBox::try_from("0 0 10 10").unwrap().to_int_rect(),
+            (0, 0, 10, 10)
+        );
+    }
+
+    #[test]
+    fn test_view_box_rounding() {
+        let view_box = ViewBox::try_from("0.4 0.6 96.5 105.4").unwrap();
+        assert_eq!(
+            view_box.round(),
+            ViewBox::new(0.0, 1.0, 97.0, 105.0).unwrap()
+        );
+        assert_eq!(
+            view_box.floor(),
+            ViewBox::new(0.0, 0.0, 96.0, 105.0).unwrap()
+        );
+        assert_eq!(
+            view_box.ceil(),
+            ViewBox::new(1.0, 1.0, 97.0, 106.0).unwrap()
+        );
+
+        let view_box = ViewBox::try_from("-0.5 -1.4 -2.6 0").unwrap();
+        assert_eq!(
+            view_box.round(),
+            ViewBox::new(-1.0, -1.0, -3.0, 0.0).unwrap()
+        );
+        assert_eq!(
+            view_box.floor(),
+            ViewBox::new(-1.0, -2.0, -3.0, 0.0).unwrap()
+        );
+        assert_eq!(view_box.ceil(), ViewBox::new(0.0, -1.0, -2.0, 0.0).unwrap());
+    }
+
+    #[test]
+    fn test_view_box_to_f32_array() {
+        let view_box = ViewBox::try_from("1 -2 96.5 105").unwrap();
+        assert_eq!(view_box.to_f32_array(), [1.0, -2.0, 96.5, 105.0]);
+
+        // 2^24 + 1 is the first integer which can't be represented
+        let view_box = ViewBox::new(0.1, 0.0, 16_777_217.0, 0.0).unwrap();
+        let [min_x, _, width, _] = view_box.to_f32_array();
+        assert_eq!(width, 16_777_216.0);
+        assert_ne!(f64::from(min_x), 0.1);
+    }
+
+    #[test]
+    fn test_view_box_same_size() {
+        let a = ViewBox::try_from("-8 -8 16 16").unwrap();
+        assert!(a.same_size(&ViewBox::try_from("0 0 16 16").unwrap()));
+        assert!(a.same_size(&ViewBox::try_from("100 -3.5 -16 16").unwrap()));
+        assert!(!a.same_size(&ViewBox::try_from("-8 -8 16 17").unwrap()));
+        assert!(!a.same_size(&ViewBox::try_from("-8 -8 8 16").unwrap()));
+    }
+
+    #[test]
+    fn test_view_box_intersection() {
+        let a = ViewBox::try_from("0 0 10 10").unwrap();
+
+        let overlapping = ViewBox::try_from("5 -5 10 10").unwrap();
+        assert_eq!(
+            a.intersection(&overlapping),
+            Some(ViewBox {
+                min_x: 5.0,
+                min_y: 0.0,
+                width: 5.0,
+                height: 5.0
+            })
+        );
+
+        // Negative extents are normalized before intersecting
+        let negative = ViewBox::try_from("15 15 -10 -10").unwrap();
+        assert_eq!(
+            a.intersection(&negative),
+            Some(ViewBox {
+                min_x: 5.0,
+                min_y: 5.0,
+                width: 5.0,
+                height: 5.0
+            })
+        );
+
+        // Touching edges have no common area
+        let touching = ViewBox::try_from("10 0 10 10").unwrap();
+        assert_eq!(a.intersection(&touching), None);
+
+        let disjoint = ViewBox::try_from("20 20 5 5").unwrap();
+        assert_eq!(a.intersection(&disjoint), None);
+    }
+
+    #[test]
+    fn test_constructors() {
+        assert_eq!(
+            ViewBox::new(0.0, 1.0, 99.0, 100.0).unwrap(),
+            ViewBox::try_from("0 1 99 100").unwrap()
+        );
+        assert_eq!(
+            Width::new(2.0, Unit::Em).unwrap(),
+            Width {
+                width: 2.0,
+                unit: Unit::Em
+            }
+        );
+        assert_eq!(
+            Height::new(10.0, Unit::Cm).unwrap(),
+            Height {
+                height: 10.0,
+                unit: Unit::Cm
+            }
+        );
+
+        for invalid in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert!(Width::new(invalid, Unit::Px).is_err());
+            assert!(Height::new(invalid, Unit::Px).is_err());
+            assert!(ViewBox::new(invalid, 0.0, 1.0, 1.0).is_err());
+            assert!(ViewBox::new(0.0, 0.0, 1.0, invalid).is_err());
+        }
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_unit_scale_factor() {
+        let cases = vec![
+            (Unit::Px, Some(1.0)),
+            (Unit::In, Some(96.0)),
+            (Unit::Cm, Some(96.0 / 2.54)),
+            (Unit::Mm, Some(96.0 / 25.4)),
+            (Unit::Pt, Some(96.0 / 72.0)),
+            (Unit::Pc, Some(16.0)),
+            (Unit::Em, None),
+            (Unit::Ex, None),
+            (Unit::Percent, None),
+        ];
+        for (unit, expected) in cases {
+            assert_eq!(unit.scale_factor(96.0), expected, "{unit}");
+        }
+        assert_eq!(Unit::Px.scale_factor(300.0), Some(1.0));
+        assert_eq!(Unit::Pt.scale_factor(144.0), Some(2.0));
+    }
+
+    #[test]
+    fn test_width() {
+        let tests = vec![
+            (
+                "100em",
+                Width {
+                    width: 100.0,
+                    unit: Unit::Em,
+                },
+            ),
+            (
+                "100",
+                Width {
+                    width: 100.0,
+                    unit: Unit::Em,
+                },
+            ),
+            (
+                "-10.0px",
+                Width {
+                    width: -10.0,
+                    unit: Unit::Px,
+                },
+            ),
+            (
+                "100em",
+                Width {
+                    width: 100.0,
+                    unit: Unit::Em,
+                },
+            ),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(Width::try_from(input).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_height() {
+        let tests = vec![
+            (
+                "100em",
+                Height {
+                    height: 100.0,
+                    unit: Unit::Em,
+                },
+            ),
+            (
+                "100",
+                Height {
+                    height: 100.0,
+                    unit: Unit::Em,
+                },
+            ),
+            (
+                "-10.0px",
+                Height {
+                    height: -10.0,
+                    unit: Unit::Px,
+                },
+            ),
+            (
+                "100em",
+                Height {
+                    height: 100.0,
+                    unit: Unit::Em,
+                },
+            ),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(Height::try_from(input).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_parse_cow() {
+        let svg = r#"<svg viewBox="0 0 10 20"/>"#;
+        let expected = Some(ViewBox::from_dimensions(10.0, 20.0));
+
+        let meta = Metadata::parse_cow(Cow::Borrowed(svg)).unwrap();
+        assert_eq!(meta.view_box, expected);
+
+        let owned: Cow<'_, str> = Cow::Owned(svg.to_string());
+        let meta = Metadata::parse_cow(owned).unwrap();
+        assert_eq!(meta.view_box, expected);
+
+        assert!(Metadata::parse_cow(Cow::Owned("<svg".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_parse_with_stats() {
+        let input = fs::read_to_string("fixtures/test.svg").unwrap();
+        let (meta, stats) = Metadata::parse_with_stats(&input).unwrap();
+        assert_eq!(meta, Metadata::parse(&input).unwrap());
+        assert_eq!(stats.element_count, 8);
+        assert_eq!(stats.source_len, input.len());
+
+        assert!(Metadata::parse_with_stats("<svg").is_err());
+    }
+
+    #[test]
+    fn test_parse_with_limit() {
+        let svg = r#"<svg width="10px"/>"#;
+        assert_eq!(svg.len(), 19);
+        assert!(Metadata::parse_with_limit(svg, 100).is_ok());
+        assert!(Metadata::parse_with_limit(svg, 19).is_ok());
+        let err = Metadata::parse_with_limit(svg, 18).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InputTooLarge);
+
+        let len = usize::try_from(fs::metadata("fixtures/test.svg").unwrap().len()).unwrap();
+        assert!(Metadata::parse_file_with_limit("fixtures/test.svg", len).is_ok());
+        let err = Metadata::parse_file_with_limit("fixtures/test.svg", len - 1).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InputTooLarge);
+    }
+
+    #[test]
+    fn test_parse_batch() {
+        let inputs = vec![
+            r#"<svg width="1px"/>"#.to_string(),
+            "not xml".to_string(),
+            r#"<svg width="invalid"/>"#.to_string(),
+            r#"<svg width="4px"/>"#.to_string(),
+        ];
+        let results = Metadata::parse_batch(&inputs);
+        assert_eq!(results.len(), inputs.len());
+        assert_eq!(results[0].as_ref().unwrap().width(), Some(1.0));
+        assert!(results[1].is_err());
+        assert_eq!(results[2].as_ref().unwrap().width(), None);
+        assert_eq!(results[3].as_ref().unwrap().width(), Some(4.0));
+
+        assert!(Metadata::parse_batch(Vec::<&str>::new()).is_empty());
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_parse_padded_unit() {
+        assert_eq!(
+            Width::try_from("100 px").unwrap(),
+            Width {
+                width: 100.0,
+                unit: Unit::Px
+            }
+        );
+        assert_eq!(
+            Height::try_from("50\t%").unwrap(),
+            Height {
+                height: 50.0,
+                unit: Unit::Percent
+            }
+        );
+        assert_eq!(
+            Dimension::try_from("1 in").unwrap(),
+            Dimension::Length(1.0, Unit::In)
+        );
+
+        let svg = r#"<svg width="100 px" height="2 cm"/>"#;
+        let meta = Metadata::parse(svg).unwrap();
+        assert_eq!(meta.width(), Some(100.0));
+        assert_eq!(meta.height.unwrap().unit, Unit::Cm);
+
+        // Strict callers still reject the padded form
+        let err = Metadata::parse_strict(svg).unwrap_err();
+        assert!(err.to_string().contains("width"), "{err}");
+        let err = Metadata::parse_strict(r#"<svg height="2 cm"/>"#).unwrap_err();
+        assert!(err.to_string().contains("height"), "{err}");
+        assert!(Metadata::parse_strict(r#"<svg width="100px" height="100 "/>"#).is_ok());
+    }
+
+    #[test]
+    fn test_parse_data_uri() {
+        let expected = Some(ViewBox::from_dimensions(10.0, 20.0));
+
+        let uri = "data:image/svg+xml;base64,PHN2ZyB4bWxucz0iaHR0cDovL3d3dy53My5vcmcvMjAwMC9zdmciIHZpZXdCb3g9IjAgMCAxMCAyMCIvPg==";
+        assert_eq!(Metadata::parse_data_uri(uri).unwrap().view_box, expected);
+        // Unpadded, wrapped, and with a charset
+        let uri = "data:image/svg+xml;charset=utf-8;base64,PHN2ZyB4bWxucz0iaHR0cDovL3d3dy53My5vcmcv\n  MjAwMC9zdmciIHZpZXdCb3g9IjAgMCAxMCAyMCIvPg";
+        assert_eq!(Metadata::parse_data_uri(uri).unwrap().view_box, expected);
+
+        let uri = "data:image/svg+xml,%3Csvg%20xmlns%3D%22http%3A//www.w3.org/2000/svg%22%20viewBox%3D%220%200%2010%2020%22/%3E";
+        assert_eq!(Metadata::parse_data_uri(uri).unwrap().view_box, expected);
+        // Quotes are often left unencoded
+        let uri = "DATA:Image/SVG+XML;utf8,<svg viewBox='0 0 10 20'/>";
+        assert_eq!(Metadata::parse_data_uri(uri).unwrap().view_box, expected);
+
+        let err = Metadata::parse_data_uri("data:image/png;base64,iVBORw0KGgo=").unwrap_err();
+        assert!(err.to_string().contains("image/png"), "{err}");
+        assert!(Metadata::parse_data_uri("<svg/>").is_err());
+        assert!(Metadata::parse_data_uri("data:image/svg+xml;base64").is_err());
+        assert!(Metadata::parse_data_uri("data:image/svg+xml;base64,PHN2Zy*+").is_err());
+        assert!(Metadata::parse_data_uri("data:image/svg+xml;base64,PHN2Z").is_err());
+        assert!(Metadata::parse_data_uri("data:image/svg+xml,%3Csvg/%3").is_err());
+    }
+
+    #[test]
+    fn test_parse_diagnostic() {
+        let clean = r#"<svg viewBox="0 0 10 10"><rect width="10" height="10"/></svg>"#;
+        let (meta, path) = Metadata::parse_diagnostic(clean).unwrap();
+        assert_eq!(path, ParsePath::FullDom);
+        assert_eq!(meta, Metadata::parse(clean).unwrap());
+
+        let truncated = &clean[..clean.len() - 10];
+        assert!(Metadata::parse(truncated).is_err());
+        let (meta, path) = Metadata::parse_diagnostic(truncated).unwrap();
+        assert_eq!(path, ParsePath::HeaderFastPath);
+        assert_eq!(meta.view_box, Some(ViewBox::from_dimensions(10.0, 10.0)));
+
+        let (meta, path) = Metadata::parse_diagnostic(r#"<svg viewBox="0 0 96 105;"/>"#).unwrap();
+        assert_eq!(path, ParsePath::Recovered);
+        assert_eq!(meta.view_box, Some(ViewBox::from_dimensions(96.0, 105.0)));
+
+        let (meta, path) = Metadata::parse_diagnostic("<svg width=100px><rect>").unwrap();
+        assert_eq!(path, ParsePath::Relaxed);
+        assert_eq!(meta.width(), Some(100.0));
+
+        assert!(Metadata::parse_diagnostic("<svg").is_err());
+    }
+
+    #[test]
+    fn test_parse_strict() {
+        let svg = r#"<svg viewBox="0 0 96" width="10px" xmlns="http://www.w3.org/2000/svg"/>"#;
+        let meta = Metadata::parse(svg).unwrap();
+        assert_eq!(meta.view_box, None);
+        assert_eq!(meta.width(), Some(10.0));
+
+        let err = Metadata::parse_strict(svg).unwrap_err();
+        assert!(err.to_string().contains("viewBox"), "{err}");
+
+        let err = Metadata::parse_strict(r#"<svg width="10foo"/>"#).unwrap_err();
+        assert!(err.to_string().contains("width"), "{err}");
+
+        let svg = r#"<svg viewBox="0 0 96 105" width="calc(100% - 1px)" height="1in"/>"#;
+        assert_eq!(
+            Metadata::parse_strict(svg).unwrap(),
+            Metadata::parse(svg).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_recover() {
+        let options = ParseOptions {
+            recover: true,
+            ..ParseOptions::default()
+        };
+        let expected = Some(ViewBox {
+            min_x: 0.0,
+            min_y: 0.0,
+            width: 96.0,
+            height: 105.0,
+        });
+
+        for view_box in [
+            "0 0 96 105;",
+            "0,0,96,105 /* exported */",
+            " 0 0 96 105 ; x",
+        ] {
+            let svg = format!(r#"<svg viewBox="{view_box}"/>"#);
+
+            let meta = Metadata::parse(&svg).unwrap();
+            assert_eq!(meta.view_box, None);
+            assert!(!meta.recovered());
+            assert!(Metadata::parse_strict(&svg).is_err());
+
+            let meta = Metadata::parse_with_options(&svg, options).unwrap();
+            assert_eq!(meta.view_box, expected, "{view_box}");
+            assert!(meta.recovered());
+        }
+
+        // Valid values don't need recovery
+        let meta = Metadata::parse_with_options(r#"<svg viewBox="0 0 96 105"/>"#, options).unwrap();
+        assert_eq!(meta.view_box, expected);
+        assert!(!meta.recovered());
+
+        // Less than four numbers can't be recovered
+        let meta = Metadata::parse_with_options(r#"<svg viewBox="0 0 96;"/>"#, options).unwrap();
+        assert_eq!(meta.view_box, None);
+        assert!(!meta.recovered());
+    }
+
+    #[test]
+    fn test_view_box_line_endings() {
+        let expected = ViewBox::new(0.0, 0.0, 96.0, 105.0).unwrap();
+        for view_box in [
+            "0\r\n0\r\n96\r\n105",
+            "0\r0\r96\r105",
+            "0,\r\n0,\r\n96,\r\n105",
+            "\r\n  0 0 96 105\r\n",
+            "\r0 0 96 105\r",
+        ] {
+            assert_eq!(
+                ViewBox::try_from(view_box).unwrap(),
+                expected,
+                "{view_box:?}"
+            );
+
+            // XML normalizes line breaks in attribute values, but the
+            // result has to be the same
+            let svg = format!("<svg viewBox=\"{view_box}\"/>");
+            let meta = Metadata::parse_strict(&svg).unwrap();
+            assert_eq!(meta.view_box, Some(expected), "{view_box:?}");
+        }
+
+        // Blank lines don't result in empty elements
+        assert_eq!(
+            ViewBox::try_from("0\r\n\r\n0\r\n\r\n96\r\n\r\n105").unwrap(),
+            expected
+        );
+        // A separator may contain at most one comma
+        assert!(ViewBox::try_from("0,\r\n,0 96 105").is_err());
+    }
+
+    #[test]
+    fn test_neg() {
+        let width = Width::try_from("10px").unwrap();
+        assert_eq!(-width, Width::try_from("-10px").unwrap());
+        assert_eq!(-(-width), width);
+        assert_eq!((-width).to_string(), "-10px");
+
+        let height = Height::try_from("-2.5cm").unwrap();
+        assert_eq!(-height, Height::new(2.5, Unit::Cm).unwrap());
+        assert_eq!((-Height::new(0.0, Unit::Em).unwrap()).unit, Unit::Em);
+    }
+
+    #[test]
+    fn test_eq_physical() {
+        let width = |s| Width::try_from(s).unwrap();
+        assert!(width("1in").eq_physical(&width("72pt"), 96.0));
+        assert!(width("1in").eq_physical(&width("2.54cm"), 96.0));
+        assert!(width("1in").eq_physical(&width("96px"), 96.0));
+        assert!(width("1in").eq_physical(&width("137.5px"), 137.5));
+        assert!(!width("1in").eq_physical(&width("96px"), 72.0));
+        assert!(!width("1cm").eq_physical(&width("1in"), 96.0));
+
+        // Font-relative units and percentages are only comparable to
+        // the same unit
+        assert!(width("2em").eq_physical(&width("2em"), 96.0));
+        assert!(!width("2em").eq_physical(&width("32px"), 96.0));
+        assert!(!width("100%").eq_physical(&width("1in"), 96.0));
+
+        let height = |s| Height::try_from(s).unwrap();
+        assert!(height("6pc").eq_physical(&height("25.4mm"), 96.0));
+        assert!(!height("1pc").eq_physical(&height("1pt"), 96.0));
+    }
+
+    #[test]
+    fn test_empty_unit() {
+        for unit in ["", " ", "\t\n"] {
+            let err = Unit::try_from(unit).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::EmptyUnit, "{unit:?}");
+            assert_eq!(err.to_string(), "Empty unit");
+        }
+        assert_eq!(
+            Unit::try_from("furlong").unwrap_err().kind(),
+            ErrorKind::Other
+        );
+
+        // A trailing space falls back to the default unit
+        assert_eq!(
+            Width::try_from("100 ").unwrap(),
+            Width::try_from("100").unwrap()
+        );
+        assert_eq!(
+            Dimension::try_from("100 ").unwrap(),
+            Dimension::Length(100.0, Unit::Em)
+        );
+        let meta = Metadata::parse(r#"<svg width="100 "/>"#).unwrap();
+        assert_eq!(meta.width, Some(Width::new(100.0, Unit::Em).unwrap()));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_unit_all_and_category() {
+        let all = Unit::all();
+        for unit in all {
+            // Fails to compile if a unit is added, as a reminder to add
+            // it to `Unit::all`
+            match unit {
+                Unit::Em
+                | Unit::Ex
+                | Unit::Px
+                | Unit::Pt
+                | Unit::Pc
+                | Unit::Cm
+                | Unit::Mm
+                | Unit::In
+                | Unit::Percent => {}
+            }
+            assert_eq!(all.iter().filter(|u| *u == unit).count(), 1, "{unit:?}");
+            assert_eq!(Unit::try_from(unit.to_string().as_str()).unwrap(), *unit);
+        }
+        assert_eq!(all.len(), 9);
+
+        let count = |category| all.iter().filter(|u| u.category() == category).count();
+        assert_eq!(count(UnitCategory::Absolute), 6);
+        assert_eq!(count(UnitCategory::FontRelative), 2);
+        assert_eq!(count(UnitCategory::Viewport), 1);
+        for unit in all {
+            let absolute = unit.category() == UnitCategory::Absolute;
+            assert_eq!(unit.scale_factor(96.0).is_some(), absolute, "{unit:?}");
+        }
+    }
+
+    #[test]
+    fn test_view_box_clamp_point() {
+        let view_box = ViewBox::try_from("10 20 100 50").unwrap();
+        // Inside and on the edges
+        assert_eq!(view_box.clamp_point(50.0, 30.0), (50.0, 30.0));
+        assert_eq!(view_box.clamp_point(10.0, 70.0), (10.0, 70.0));
+        // Outside of each edge
+        assert_eq!(view_box.clamp_point(0.0, 30.0), (10.0, 30.0));
+        assert_eq!(view_box.clamp_point(200.0, 30.0), (110.0, 30.0));
+        assert_eq!(view_box.clamp_point(50.0, -5.0), (50.0, 20.0));
+        assert_eq!(view_box.clamp_point(50.0, 100.0), (50.0, 70.0));
+        // Outside of a corner
+        assert_eq!(view_box.clamp_point(-1.0, 1000.0), (10.0, 70.0));
+
+        let flipped = ViewBox::try_from("110 70 -100 -50").unwrap();
+        assert_eq!(flipped.clamp_point(0.0, 100.0), (10.0, 70.0));
+    }
+
+    #[test]
+    fn test_preserve_aspect_ratio() {
+        let parse = |s: &str| PreserveAspectRatio::try_from(s);
+        assert_eq!(parse("xMidYMid").unwrap(), PreserveAspectRatio::default());
+        assert_eq!(
+            parse(" defer xMinYMax  slice ").unwrap(),
+            PreserveAspectRatio {
+                align: Align::XMinYMax,
+                slice: true,
+            }
+        );
+        assert_eq!(parse("none").unwrap().align, Align::None);
+        for invalid in ["", "defer", "xmidymid", "xMidYMid cover", "none meet slice"] {
+            assert!(parse(invalid).is_err(), "{invalid:?}");
+        }
+
+        for (align, keyword) in Align::KEYWORDS {
+            for slice in [false, true] {
+                let preserve = PreserveAspectRatio { align, slice };
+                assert!(preserve.to_string().starts_with(keyword));
+                assert_eq!(parse(&preserve.to_string()).unwrap(), preserve);
+            }
+        }
+    }
+
+    #[test]
+    fn test_view_box_transform_to() {
+        let preserve = |s: &str| Some(PreserveAspectRatio::try_from(s).unwrap());
+
+        // Identity
+        let view_box = ViewBox::try_from("-10 5 20 40").unwrap();
+        assert_eq!(
+            view_box.transform_to(&view_box, None),
+            [1.0, 0.0, 0.0, 1.0, 0.0, 0.0]
+        );
+
+        // Pure scale, the same for all alignments
+        let view_box = ViewBox::try_from("0 0 24 24").unwrap();
+        let target = ViewBox::try_from("0 0 96 96").unwrap();
+        for align in ["none", "xMinYMin", "xMaxYMax slice"] {
+            assert_eq!(
+                view_box.transform_to(&target, preserve(align)),
+                [4.0, 0.0, 0.0, 4.0, 0.0, 0.0]
+            );
+        }
+
+        // Letterboxed: 100x50 into 200x200 is scaled by 2, leaving 100px
+        let view_box = ViewBox::try_from("10 0 100 50").unwrap();
+        let target = ViewBox::try_from("0 0 200 200").unwrap();
+        assert_eq!(
+            view_box.transform_to(&target, None),
+            [2.0, 0.0, 0.0, 2.0, -20.0, 50.0]
+        );
+        assert_eq!(
+            view_box.transform_to(&target, preserve("xMinYMin")),
+            [2.0, 0.0, 0.0, 2.0, -20.0, 0.0]
+        );
+        assert_eq!(
+            view_box.transform_to(&target, preserve("xMaxYMax meet")),
+            [2.0, 0.0, 0.0, 2.0, -20.0, 100.0]
+        );
+        // Sliced: scaled by 4, cropping 200px horizontally
+        assert_eq!(
+            view_box.transform_to(&target, preserve("xMidYMid slice")),
+            [4.0, 0.0, 0.0, 4.0, -140.0, 0.0]
+        );
+        assert_eq!(
+            view_box.transform_to(&target, preserve("none")),
+            [2.0, 0.0, 0.0, 4.0, -20.0, 0.0]
+        );
+
+        // The target may have an offset
+        let target = ViewBox::try_from("5 5 200 200").unwrap();
+        assert_eq!(
+            view_box.transform_to(&target, None),
+            [2.0, 0.0, 0.0, 2.0, -15.0, 55.0]
+        );
+    }
+
+    #[test]
+    fn test_view_box_matches_str() {
+        let view_box = ViewBox::try_from("0 0 96 105").unwrap();
+        assert!(view_box.matches_str("0 0 96 105"));
+        assert!(view_box.matches_str(" 0.0 0e0 96 105 "));
+        assert!(!view_box.matches_str("0 0 96 106"));
+        assert!(!view_box.matches_str("0 0 96"));
+        assert!(!view_box.matches_str(""));
+
+        assert_eq!(view_box, "0 0 96 105");
+        assert_ne!(view_box, "1 0 96 105");
+        assert_ne!(view_box, "invalid");
+        assert_ne!(ViewBox::new(0.0, 0.0, f64::MAX, 1.0).unwrap(), "NaN 0 1 1");
+    }
+
+    #[test]
+    fn test_view_box_lerp() {
+        let from = ViewBox::try_from("0.1 0 100 100").unwrap();
+        let to = ViewBox::try_from("0.3 -20 10 40").unwrap();
+        assert_eq!(from.lerp(&to, 0.0), from);
+        assert_eq!(from.lerp(&to, 1.0), to);
+        let middle = from.lerp(&to, 0.5);
+        assert!((middle.min_x - 0.2).abs() < 1e-12);
+        assert_eq!(middle.min_y, -10.0);
+        assert_eq!(middle.width, 55.0);
+        assert_eq!(middle.height, 70.0);
+
+        // Not clamped
+        assert_eq!(from.lerp(&to, 2.0).width, -80.0);
+        assert_eq!(from.lerp(&from, 0.7), from);
+    }
+
+    #[test]
+    fn test_view_box_pad_to_aspect() {
+        let square = ViewBox::try_from("0 0 90 90").unwrap();
+
+        let wide = square.pad_to_aspect(16.0 / 9.0);
+        assert_eq!(wide, ViewBox::new(-35.0, 0.0, 160.0, 90.0).unwrap());
+        assert!((wide.width / wide.height - 16.0 / 9.0).abs() < 1e-9);
+
+        let tall = square.pad_to_aspect(9.0 / 16.0);
+        assert_eq!(tall, ViewBox::new(0.0, -35.0, 90.0, 160.0).unwrap());
+
+        // Already matching boxes are left alone
+        assert_eq!(wide.pad_to_aspect(16.0 / 9.0), wide);
+        assert_eq!(square.pad_to_aspect(1.0), square);
+
+        // Invalid targets and empty boxes
+        assert_eq!(square.pad_to_aspect(0.0), square);
+        assert_eq!(square.pad_to_aspect(f64::NAN), square);
+        let empty = ViewBox::try_from("0 0 0 10").unwrap();
+        assert_eq!(empty.pad_to_aspect(2.0), empty);
+    }
+
+    #[test]
+    fn test_view_box_inset_expand() {
+        let view_box = ViewBox::try_from("0 0 100 100").unwrap();
+        assert_eq!(
+            view_box.inset(10.0),
+            ViewBox::new(10.0, 10.0, 80.0, 80.0).unwrap()
+        );
+        assert_eq!(view_box.inset(10.0).expand(10.0), view_box);
+        assert_eq!(view_box.inset(0.0), view_box);
+
+        // Over-inset collapses onto the center
+        assert_eq!(
+            view_box.inset(60.0),
+            ViewBox::new(50.0, 50.0, 0.0, 0.0).unwrap()
+        );
+
+        // Only the smaller extent collapses
+        let wide = ViewBox::try_from("0 0 200 100").unwrap();
+        assert_eq!(
+            wide.inset(60.0),
+            ViewBox::new(60.0, 50.0, 80.0, 0.0).unwrap()
+        );
+
+        let flipped = ViewBox::try_from("100 100 -100 -100").unwrap();
+        assert_eq!(
+            flipped.inset(10.0),
+            ViewBox::new(10.0, 10.0, 80.0, 80.0).unwrap()
+        );
+        assert_eq!(view_box.expand(-10.0), view_box.inset(10.0));
+    }
+
+    #[test]
+    fn test_view_box_to_rect_path() {
+        let view_box = ViewBox::try_from("0 0 10 10").unwrap();
+        assert_eq!(view_box.to_rect_path(), "M 0,0 h 10 v 10 h -10 Z");
+
+        let view_box = ViewBox::try_from("0.5 -1 2.25 3").unwrap();
+        assert_eq!(view_box.to_rect_path(), "M 0.5,-1 h 2.25 v 3 h -2.25 Z");
+    }
+
+    #[test]
+    fn test_view_box_pixel_mapping() {
+        let view_box = ViewBox::try_from("10 20 100 50").unwrap();
+        let corners = [
+            ((10.0, 20.0), (0.0, 0.0)),
+            ((110.0, 20.0), (400.0, 0.0)),
+            ((10.0, 70.0), (0.0, 100.0)),
+            ((110.0, 70.0), (400.0, 100.0)),
+        ];
+        for ((vb_x, vb_y), pixel) in corners {
+            assert_eq!(view_box.to_pixel(vb_x, vb_y, 400.0, 100.0), pixel);
+            assert_eq!(
+                view_box.from_pixel(pixel.0, pixel.1, 400.0, 100.0),
+                (vb_x, vb_y)
+            );
+        }
+        assert_eq!(view_box.to_pixel(60.0, 45.0, 400.0, 100.0), (200.0, 50.0));
+    }
+
+    #[test]
+    fn test_view_box_diagonal() {
+        let diagonal = |s| ViewBox::try_from(s).unwrap().diagonal();
+        assert_eq!(diagonal("0 0 3 4"), 5.0);
+        assert_eq!(diagonal("10 10 -3 -4"), 5.0);
+        assert_eq!(diagonal("0 0 0 0"), 0.0);
+    }
+
+    #[test]
+    fn test_view_box_grid_points() {
+        let view_box = ViewBox::try_from("0 0 100 100").unwrap();
+        assert_eq!(
+            view_box.grid_points(3, 3),
+            [
+                (0.0, 0.0),
+                (50.0, 0.0),
+                (100.0, 0.0),
+                (0.0, 50.0),
+                (50.0, 50.0),
+                (100.0, 50.0),
+                (0.0, 100.0),
+                (50.0, 100.0),
+                (100.0, 100.0),
+            ]
+        );
+        assert_eq!(view_box.grid_points(1, 1), [(50.0, 50.0)]);
+        assert_eq!(view_box.grid_points(1, 2), [(50.0, 0.0), (50.0, 100.0)]);
+        assert!(view_box.grid_points(0, 3).is_empty());
+        assert!(view_box.grid_points(3, 0).is_empty());
+
+        let flipped = ViewBox::try_from("10 10 -10 -20").unwrap();
+        assert_eq!(flipped.grid_points(2, 2)[0], (0.0, -10.0));
+        assert_eq!(flipped.grid_points(2, 2)[3], (10.0, 10.0));
+    }
+
+    #[test]
+    fn test_view_box_split_into_grid() {
+        let view_box = ViewBox::try_from("0 0 100 100").unwrap();
+        assert_eq!(
+            view_box.split_into_grid(2, 2),
+            vec![
+                ViewBox::new(0.0, 0.0, 50.0, 50.0).unwrap(),
+                ViewBox::new(50.0, 0.0, 50.0, 50.0).unwrap(),
+                ViewBox::new(0.0, 50.0, 50.0, 50.0).unwrap(),
+                ViewBox::new(50.0, 50.0, 50.0, 50.0).unwrap(),
+            ]
+        );
+
+        let tiles = ViewBox::try_from("10 20 90 30")
+            .unwrap()
+            .split_into_grid(3, 1);
+        assert_eq!(tiles.len(), 3);
+        assert_eq!(tiles[2], ViewBox::new(70.0, 20.0, 30.0, 30.0).unwrap());
+
+        assert!(view_box.split_into_grid(0, 2).is_empty());
+        assert!(view_box.split_into_grid(2, 0).is_empty());
+    }
+
+    #[test]
+    fn test_view_box_fit_scale() {
+        let view_box = ViewBox::try_from("0 0 100 50").unwrap();
+
+        // Wider target: height is the limiting axis for meet
+        assert_eq!(view_box.fit_scale(400.0, 100.0, false), 2.0);
+        assert_eq!(view_box.fit_scale(400.0, 100.0, true), 4.0);
+
+        // Taller target: width is the limiting axis for meet
+        assert_eq!(view_box.fit_scale(100.0, 400.0, false), 1.0);
+        assert_eq!(view_box.fit_scale(100.0, 400.0, true), 8.0);
+
+        // Same aspect ratio
+        assert_eq!(view_box.fit_scale(50.0, 25.0, false), 0.5);
+        assert_eq!(view_box.fit_scale(50.0, 25.0, true), 0.5);
+
+        let flipped = ViewBox::try_from("100 50 -100 -50").unwrap();
+        assert_eq!(flipped.fit_scale(400.0, 100.0, false), 2.0);
+    }
+
+    #[test]
+    fn test_parse_euro_decimals() {
+        assert_eq!(euro_decimal_values("0,0 96,5"), Some(vec![0.0, 96.5]));
+        assert_eq!(euro_decimal_values("0,0,96,105"), None);
+        assert_eq!(euro_decimal_values("0 0 96 105"), None);
+
+        let options = ParseOptions {
+            parse_euro_decimals: true,
+            ..ParseOptions::default()
+        };
+        let svg = r#"<svg viewBox="0,0 0,0 96,5 105,2"/>"#;
+        assert_eq!(Metadata::parse(svg).unwrap().view_box, None);
+        let meta = Metadata::parse_with_options(svg, options).unwrap();
+        assert_eq!(
+            meta.view_box,
+            Some(ViewBox::new(0.0, 0.0, 96.5, 105.2).unwrap())
+        );
+        assert!(meta.recovered());
+
+        let meta =
+            Metadata::parse_with_options(r#"<svg viewBox="0 0 96,5 105,2"/>"#, options).unwrap();
+        assert_eq!(
+            meta.view_box,
+            Some(ViewBox::new(0.0, 0.0, 96.5, 105.2).unwrap())
+        );
+
+        // Valid viewBoxes are unaffected
+        let meta =
+            Metadata::parse_with_options(r#"<svg viewBox="0, 0, 96, 105"/>"#, options).unwrap();
+        assert_eq!(
+            meta.view_box,
+            Some(ViewBox::new(0.0, 0.0, 96.0, 105.0).unwrap())
+        );
+        assert!(!meta.recovered());
+
+        // Two numbers with decimal commas are also four valid numbers
+        let meta = Metadata::parse_with_options(r#"<svg viewBox="0,0 96,5"/>"#, options).unwrap();
+        assert_eq!(
+            meta.view_box,
+            Some(ViewBox::new(0.0, 0.0, 96.0, 5.0).unwrap())
+        );
+        assert!(!meta.recovered());
+    }
+
+    #[test]
+    fn test_dimension_calc() {
+        assert_eq!(
+            Dimension::try_from(" CALC(100% - 2em) ").unwrap(),
+            Dimension::Calc("CALC(100% - 2em)".to_string())
+        );
+        assert_eq!(
+            Dimension::try_from("10cm").unwrap(),
+            Dimension::Length(10.0, Unit::Cm)
+        );
+        assert!(Width::try_from("calc(100% - 20px)").is_err());
+
+        let svg = r#"<svg width="calc(100% - 20px)" height="10px" xmlns="http://www.w3.org/2000/svg"></svg>"#;
+        let meta = Metadata::parse(svg).unwrap();
+        assert_eq!(meta.width, None);
+        assert_eq!(
+            meta.width_dimension(),
+            Some(&Dimension::Calc("calc(100% - 20px)".to_string()))
+        );
+        assert_eq!(
+            meta.height_dimension(),
+            Some(&Dimension::Length(10.0, Unit::Px))
+        );
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_dimension_percentage() {
+        assert_eq!(
+            Dimension::try_from("50%").unwrap(),
+            Dimension::Percentage(50.0)
+        );
+        assert_eq!(
+            Dimension::try_from("-12.5%").unwrap(),
+            Dimension::Percentage(-12.5)
+        );
+
+        // `Width` and `Height` keep using `Unit::Percent`
+        let svg = r#"<svg viewBox="0 0 200 100" width="50%" height="10px"/>"#;
+        let meta = Metadata::parse(svg).unwrap();
+        assert_eq!(meta.width, Some(Width::new(50.0, Unit::Percent).unwrap()));
+        assert_eq!(meta.width_dimension(), Some(&Dimension::Percentage(50.0)));
+        assert_eq!(meta.width(), Some(100.0));
+        assert_eq!(
+            meta.height_dimension(),
+            Some(&Dimension::Length(10.0, Unit::Px))
+        );
+
+        // Both representations resolve the same way
+        let options = ResolveOptions::default();
+        for dimension in [
+            Dimension::Percentage(50.0),
+            Dimension::Length(50.0, Unit::Percent),
+        ] {
+            assert_eq!(dimension.resolve(200.0, &options), Some(100.0));
+        }
+        assert_eq!(
+            Dimension::Length(50.0, Unit::Px).resolve(200.0, &options),
+            Some(50.0)
+        );
+        assert_eq!(
+            Dimension::Length(1.0, Unit::Em).resolve(200.0, &options),
+            None
+        );
+        assert_eq!(
+            Dimension::Calc("calc(1px)".to_string()).resolve(200.0, &options),
+            None
+        );
+
+        // Canonical forms are lengths
+        let canonical = meta.canonicalize(96.0);
+        assert_eq!(
+            canonical.width_dimension(),
+            Some(&Dimension::Length(100.0, Unit::Px))
+        );
+    }
+
+    #[test]
+    fn test_format_with_precision() {
+        let width = Width::try_from("10.0cm").unwrap();
+        assert_eq!(width.to_string(), "10cm");
+        assert_eq!(width.format_with_precision(0), "10cm");
+        assert_eq!(width.format_with_precision(2), "10.00cm");
+        assert_eq!(format!("{width:.1}"), "10.0cm");
+
+        let height = Height::try_from("-2.345%").unwrap();
+        assert_eq!(height.to_string(), "-2.345%");
+        assert_eq!(height.format_with_precision(0), "-2%");
+        assert_eq!(height.format_with_precision(2), "-2.35%");
+
+        // Formatted values can be parsed again
+        assert_eq!(Width::try_from(width.to_string().as_str()).unwrap(), width);
+    }
+
+    #[test]
+    fn test_width_height_percent() {
+        let svg = r#"<svg viewBox="0 1 99 100" width="100%" height="100%" xmlns="http://www.w3.org/2000/svg">
+  <rect x="0" y="0" width="100%" height="100%"/>
+</svg>"#;
+
+        let meta = Metadata::parse(svg).unwrap();
+        assert_eq!(meta.width(), Some(99.0));
+        assert_eq!(meta.height(), Some(100.0));
+
+        let svg = r#"<svg viewBox="0 1 80 200" width="50%" height="20%" xmlns="http://www.w3.org/2000/svg"></svg>"#;
+
+        let meta = Metadata::parse(svg).unwrap();
+        assert_eq!(meta.width(), Some(40.0));
+        assert_eq!(meta.height(), Some(40.0));
+    }
+
+    #[test]
+    fn test_width_height_percent_axis() {
+        // Percentages resolve against the matching axis of the viewBox
+        let svg = r#"<svg viewBox="0 0 200 50" width="50%" height="50%"/>"#;
+        let meta = Metadata::parse(svg).unwrap();
+        assert_eq!(meta.width(), Some(100.0));
+        assert_eq!(meta.height(), Some(25.0));
+
+        let options = ResolveOptions::default();
+        assert_eq!(meta.resolve_width(&options), Some(100.0));
+        assert_eq!(meta.resolve_height(&options), Some(25.0));
+
+        let canonical = meta.canonicalize(96.0);
+        assert_eq!(canonical.width, Some(Width::new(100.0, Unit::Px).unwrap()));
+        assert_eq!(canonical.height, Some(Height::new(25.0, Unit::Px).unwrap()));
+    }
+
+    #[test]
+    fn test_width_height_or() {
+        let meta =
+            Metadata::parse(r#"<svg viewBox="0 0 24 12" width="48" height="50%"/>"#).unwrap();
+        assert_eq!(meta.width_or(100.0), 48.0);
+        assert_eq!(meta.height_or(100.0), 6.0);
+
+        let meta = Metadata::parse(r#"<svg viewBox="0 0 24 12" width="invalid"/>"#).unwrap();
+        assert_eq!(meta.width_or(100.0), 24.0);
+        assert_eq!(meta.height_or(100.0), 12.0);
+
+        let meta = Metadata::parse(r#"<svg width="100%"/>"#).unwrap();
+        assert_eq!(meta.width_or(100.0), 100.0);
+        assert_eq!(meta.height_or(50.0), 50.0);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_width_height_percent_without_view_box() {
+        let svg = r#"<svg width="100%" height="50%" xmlns="http://www.w3.org/2000/svg"></svg>"#;
+
+        let meta = Metadata::parse(svg).unwrap();
+        assert_eq!(
+            meta.width,
+            Some(Width {
+                width: 100.0,
+                unit: Unit::Percent
+            })
+        );
+        assert_eq!(meta.width(), None);
+        assert_eq!(meta.height(), None);
+    }
+
+    #[test]
+    fn test_presence() {
+        let cases = vec![
+            ("<svg/>", (false, false, false), 0),
+            (r#"<svg viewBox="0 0 1 1"/>"#, (true, false, false), 1),
+            (r#"<svg width="1" height="2"/>"#, (false, true, true), 2),
+            (
+                r#"<svg viewBox="0 0 1 1" width="1" height="2"/>"#,
+                (true, true, true),
+                3,
+            ),
+            // Invalid values don't count
+            (
+                r#"<svg viewBox="0 0 1" width="x"/>"#,
+                (false, false, false),
+                0,
+            ),
+        ];
+        for (svg, (has_view_box, has_width, has_height), count) in cases {
+            let presence = Metadata::parse(svg).unwrap().presence();
+            assert_eq!(
+                presence,
+                FieldPresence {
+                    has_view_box,
+                    has_width,
+                    has_height
+                },
+                "{svg}"
+            );
+            assert_eq!(presence.count(), count);
+        }
+    }
+
+    #[test]
+    fn test_raster_dimensions() {
+        let icon =
+            Metadata::parse(r#"<svg viewBox="0 0 24 24" width="24px" height="24px"/>"#).unwrap();
+        assert_eq!(icon.raster_dimensions(96.0, 1024), Some((24, 24)));
+        assert_eq!(icon.raster_dimensions(144.0, 1024), Some((36, 36)));
+        let unitless = Metadata::parse(r#"<svg width="100" height="50"/>"#).unwrap();
+        assert_eq!(unitless.raster_dimensions(96.0, 1024), Some((100, 50)));
+        assert_eq!(unitless.raster_dimensions(192.0, 100), Some((100, 50)));
+
+        let print = Metadata::parse(r#"<svg width="1in" height="0.5in"/>"#).unwrap();
+        assert_eq!(print.raster_dimensions(300.0, 4096), Some((300, 150)));
+
+        // Larger images are scaled down to fit, keeping the aspect ratio
+        let large = Metadata::parse(r#"<svg viewBox="0 0 4000 1000"/>"#).unwrap();
+        assert_eq!(large.raster_dimensions(96.0, 1000), Some((1000, 250)));
+        assert_eq!(large.raster_dimensions(192.0, 1000), Some((1000, 250)));
+        let tall = Metadata::parse(r#"<svg viewBox="0 0 10 100000"/>"#).unwrap();
+        assert_eq!(tall.raster_dimensions(96.0, 100), Some((1, 100)));
+
+        assert_eq!(icon.raster_dimensions(0.0, 1024), None);
+        assert_eq!(icon.raster_dimensions(-96.0, 1024), None);
+        assert_eq!(icon.raster_dimensions(f64::NAN, 1024), None);
+        assert_eq!(icon.raster_dimensions(96.0, 0), None);
+        for svg in [
+            "<svg/>",
+            r#"<svg width="2em" height="2em"/>"#,
+            r#"<svg viewBox="0 0 0 24"/>"#,
+            r#"<svg width="-10px" height="10px"/>"#,
+        ] {
+            let meta = Metadata::parse(svg).unwrap();
+            assert_eq!(meta.raster_dimensions(96.0, 1024), None, "{svg}");
+        }
+    }
+
+    #[test]
+    fn test_physical_size_mm() {
+        let assert_size = |svg: &str, dpi: f64, expected: Option<(f64, f64)>| {
+            let size = Metadata::parse(svg).unwrap().physical_size_mm(dpi);
+            match (size, expected) {
+                (Some((w, h)), Some((expected_w, expected_h))) => {
+                    assert!((w - expected_w).abs() < 1e-9, "{svg}: {w} != {expected_w}");
+                    assert!((h - expected_h).abs() < 1e-9, "{svg}: {h} != {expected_h}");
+                }
+                _ => assert_eq!(size, expected, "{svg}"),
+            }
+        };
+
+        assert_size(
+            r#"<svg width="96px" height="96px"/>"#,
+            96.0,
+            Some((25.4, 25.4)),
+        );
+        assert_size(
+            r#"<svg width="96px" height="96px"/>"#,
+            192.0,
+            Some((12.7, 12.7)),
+        );
+        assert_size(
+            r#"<svg width="2cm" height="72pt"/>"#,
+            300.0,
+            Some((20.0, 25.4)),
+        );
+        assert_size(r#"<svg viewBox="0 0 96 48"/>"#, 96.0, Some((25.4, 12.7)));
+        assert_size(r#"<svg width="96" height="48"/>"#, 96.0, Some((25.4, 12.7)));
+        assert_size(
+            r#"<svg viewBox="0 0 96 48" width="50%" height="10mm"/>"#,
+            96.0,
+            Some((12.7, 10.0)),
+        );
+        assert_size(r#"<svg width="2em" height="10px"/>"#, 96.0, None);
+        assert_size(r#"<svg width="100%" height="10px"/>"#, 96.0, None);
+        assert_size("<svg/>", 96.0, None);
+    }
+
+    #[cfg(feature = "color")]
+    #[test]
+    fn test_color_parse() {
+        let red = Some(Color::rgb(255, 0, 0));
+        for fill in [
+            "#f00",
+            "#ff0000",
+            "#FF0000",
+            "rgb(255,0,0)",
+            "rgb(255, 0, 0)",
+            "RGB(100% 0% 0%)",
+            "rgba(255, 0, 0, 1)",
+            "red",
+            " Red ",
+        ] {
+            let meta = Metadata::parse(format!(r#"<svg fill="{fill}"/>"#)).unwrap();
+            assert_eq!(meta.fill_color(), red, "{fill}");
+            assert_eq!(meta.fill.as_deref(), Some(fill));
+        }
+
+        let color = |s| Color::parse(s).unwrap();
+        assert_eq!(color("#0f08").a, 136);
+        assert_eq!(color("#00ff0080").a, 128);
+        assert_eq!(color("rgba(0, 0, 255, 0.5)").a, 128);
+        assert_eq!(color("rgb(0 0 255 / 25%)").a, 64);
+        assert_eq!(color("rgb(300, -5, 0)"), Color::rgb(255, 0, 0));
+        assert_eq!(color("transparent").a, 0);
+        assert_eq!(color("rebeccapurple"), Color::rgb(102, 51, 153));
+        assert_eq!(color("AliceBlue"), Color::rgb(240, 248, 255));
+        assert_eq!(color("yellowgreen"), Color::rgb(154, 205, 50));
+        assert_eq!(color("#abc").to_string(), "#aabbcc");
+        assert_eq!(color("#aabbcc80").to_string(), "#aabbcc80");
+
+        for fill in [
+            "none",
+            "currentColor",
+            "url(#gradient)",
+            "#ff000",
+            "#ggg",
+            "rgb(1, 2)",
+            "rgb(1, 2, 3",
+            "reddish",
+            "",
+        ] {
+            assert_eq!(Color::parse(fill), None, "{fill}");
+        }
+        assert_eq!(Metadata::parse("<svg/>").unwrap().fill_color(), None);
+    }
+
+    #[test]
+    fn test_parse_prefix() {
+        let svg = r#"<?xml version="1.0"?>
+<!-- exported -->
+<!DOCTYPE svg [<!ENTITY size "96">]>
+<svg viewBox="0 0 &size; 105" data-x='a>b' width="10px">
+  <image href="a.png"/>
+</svg>"#;
+        let root_end = svg.find("px\">").unwrap() + 4;
+
+        let meta = Metadata::parse_prefix(svg, root_end).unwrap().unwrap();
+        assert_eq!(
+            meta.view_box,
+            Some(ViewBox::new(0.0, 0.0, 96.0, 105.0).unwrap())
+        );
+        assert_eq!(meta.width, Metadata::parse(svg).unwrap().width);
+        assert!(meta.images().is_empty());
+        assert_eq!(Metadata::parse_prefix(svg, usize::MAX).unwrap(), Some(meta));
+
+        // The start tag ends beyond the window
+        assert_eq!(Metadata::parse_prefix(svg, root_end - 1).unwrap(), None);
+        assert_eq!(Metadata::parse_prefix(svg, 0).unwrap(), None);
+
+        let meta = Metadata::parse_prefix(r#"<svg width="1em"/>trailing"#, 18).unwrap();
+        assert!(meta.unwrap().width.is_some());
+
+        // The window may end within a multi-byte character
+        assert_eq!(
+            Metadata::parse_prefix("<svg>äöü</svg>", 7)
+                .unwrap()
+                .unwrap()
+                .root_tag(),
+            "svg"
+        );
+        assert_eq!(Metadata::parse_prefix("<!-- ä --><svg/>", 7).unwrap(), None);
+
+        assert_eq!(Metadata::parse_prefix("not xml", 100).unwrap(), None);
+        assert!(Metadata::parse_prefix(r#"<svg width="1" width="2">"#, 100).is_err());
+    }
+
+    #[test]
+    fn test_parse_allow_partial_xml() {
+        let svg = fs::read_to_string("fixtures/test.svg").unwrap();
+        let expected = Metadata::parse(&svg).unwrap();
+        assert!(svg.starts_with("<svg"));
+        let root_end = svg.find('>').unwrap() + 1;
+
+        // Truncated right after the root start tag, within the body,
+        // and within the end tag
+        for end in [root_end, root_end + 1, svg.trim_end().len() - 1] {
+            let truncated = &svg[..end];
+            assert!(Metadata::parse(truncated).is_err());
+            let meta = Metadata::parse_allow_partial_xml(truncated).unwrap();
+            assert_eq!(meta.view_box, expected.view_box);
+            assert_eq!(meta.width, expected.width);
+            assert_eq!(meta.height, expected.height);
+        }
+
+        // Complete documents are parsed as usual
+        assert_eq!(Metadata::parse_allow_partial_xml(&svg).unwrap(), expected);
+
+        // Truncated within the root start tag
+        assert!(Metadata::parse_allow_partial_xml(&svg[..root_end - 1]).is_err());
+        assert!(Metadata::parse_allow_partial_xml("").is_err());
+    }
+
+    #[test]
+    fn test_declared_aspect_ratio() {
+        let ratio = |value: &str| {
+            let svg = format!(r#"<svg viewBox="0 0 10 10" aspect-ratio="{value}"/>"#);
+            Metadata::parse(svg).unwrap().declared_aspect_ratio()
+        };
+        assert_eq!(ratio("16 / 9"), Some(16.0 / 9.0));
+        assert_eq!(ratio("16/9"), Some(16.0 / 9.0));
+        assert_eq!(ratio("1.777"), Some(1.777));
+        assert_eq!(ratio("auto 2 / 1"), Some(2.0));
+        assert_eq!(ratio("auto"), None);
+        assert_eq!(ratio("1 / 0"), None);
+        assert_eq!(ratio("-1"), None);
+        assert_eq!(ratio("wide"), None);
+
+        // The declared ratio takes precedence over the viewBox
+        let svg = r#"<svg viewBox="0 0 10 10" aspect-ratio="16 / 9"/>"#;
+        let meta = Metadata::parse(svg).unwrap();
+        assert_eq!(meta.intrinsic_aspect_ratio(), Some(16.0 / 9.0));
+
+        let svg = r#"<svg viewBox="0 0 10 10" aspect-ratio="auto"/>"#;
+        let meta = Metadata::parse(svg).unwrap();
+        assert_eq!(meta.intrinsic_aspect_ratio(), Some(1.0));
+    }
+
+    #[test]
+    fn test_scan() {
+        let svg = r##"<svg xmlns:xlink="http://www.w3.org/1999/xlink" width="100">
+            <rect width="10" height="5"/>
+            <g><rect x="1" width="20&#x25;"/><rect/></g>
+            <use xlink:href="#a"/>
+        </svg>"##;
+        let mut widths = Vec::new();
+        let mut names = Vec::new();
+        crate::scan(svg, |name, attributes| {
+            names.push(name.to_string());
+            if name == "rect" {
+                let width = attributes.iter().find(|(k, _)| *k == "width");
+                widths.push(width.map(|(_, v)| (*v).to_string()));
+            }
+            if name == "use" {
+                assert_eq!(attributes, [("href", "#a")]);
+            }
+        })
+        .unwrap();
+        assert_eq!(names, ["svg", "rect", "g", "rect", "rect", "use"]);
+        assert_eq!(
+            widths,
+            [Some("10".to_string()), Some("20%".to_string()), None]
+        );
+
+        let mut called = false;
+        assert!(crate::scan("<svg><rect></svg>", |_, _| called = true).is_err());
+        assert!(!called);
+    }
+
+    #[test]
+    fn test_parse_relaxed() {
+        let svg = r#"<svg width=100px height = 50px viewBox="0 0 100 50" fill='a"b' hidden
+            class=icon class=ignored><rect width=10></svg>"#;
+        assert!(Metadata::parse(svg).is_err());
+        let meta = Metadata::parse_relaxed(svg).unwrap();
+        assert_eq!(meta.width, Some(Width::new(100.0, Unit::Px).unwrap()));
+        assert_eq!(meta.height, Some(Height::new(50.0, Unit::Px).unwrap()));
+        assert_eq!(meta.view_box, Some(ViewBox::from_dimensions(100.0, 50.0)));
+        assert_eq!(meta.fill.as_deref(), Some("a\"b"));
+        assert_eq!(meta.classes(), ["icon"]);
+        assert_eq!(meta.attributes()["hidden"], "");
+        assert_eq!(meta.attribute_ranges(), &AttributeRanges::default());
+
+        let meta = Metadata::parse_relaxed("<svg viewBox=0,0,10,10 width=5px/>").unwrap();
+        assert_eq!(meta.width, Some(Width::new(5.0, Unit::Px).unwrap()));
+
+        // Valid XML is parsed as usual
+        let valid = r#"<svg width="10px"><rect/></svg>"#;
+        assert_eq!(
+            Metadata::parse_relaxed(valid).unwrap(),
+            Metadata::parse(valid).unwrap()
+        );
+
+        assert!(Metadata::parse_relaxed("<svg width=10").is_err());
+        assert!(Metadata::parse_relaxed("not svg").is_err());
+    }
+
+    #[test]
+    fn test_parse_root() {
+        let svg = fs::read_to_string("fixtures/test.svg").unwrap();
+        let doc = roxmltree::Document::parse(&svg).unwrap();
+        assert_eq!(parse_root(&doc), Metadata::parse(&svg).unwrap());
+
+        // Invalid attributes are ignored
+        let doc = roxmltree::Document::parse(r#"<svg width="wide" viewBox="0 0"/>"#).unwrap();
+        let meta = parse_root(&doc);
+        assert_eq!(meta.width, None);
+        assert_eq!(meta.view_box, None);
+    }
+
+    #[test]
+    fn test_orientation() {
+        let orientation = |svg: &str| Metadata::parse(svg).unwrap().orientation();
+        assert_eq!(
+            orientation(r#"<svg viewBox="0 0 24 24"/>"#),
+            Some(Orientation::Square)
+        );
+        assert_eq!(
+            orientation(r#"<svg viewBox="0 0 20 10"/>"#),
+            Some(Orientation::Landscape)
+        );
+        assert_eq!(
+            orientation(r#"<svg viewBox="0 0 10 20"/>"#),
+            Some(Orientation::Portrait)
+        );
+        // Within the tolerance
+        assert_eq!(
+            orientation(r#"<svg width="100px" height="99.5px"/>"#),
+            Some(Orientation::Square)
+        );
+        assert_eq!(orientation("<svg/>"), None);
+
+        let meta = Metadata::parse(r#"<svg width="2cm" height="1cm"/>"#).unwrap();
+        assert!(meta.is_landscape());
+        assert!(!meta.is_square());
+        assert!(!meta.is_portrait());
+
+        let meta = Metadata::parse(r#"<svg viewBox="0 0 10 20"/>"#).unwrap();
+        assert!(meta.is_portrait());
+        let meta = Metadata::parse("<svg/>").unwrap();
+        assert!(!meta.is_square() && !meta.is_landscape() && !meta.is_portrait());
+    }
+
+    #[test]
+    fn test_namespaces() {
+        let meta = Metadata::parse_file("fixtures/openclipart-Gat3.svg").unwrap();
+        let namespaces = meta.namespaces();
+        assert_eq!(
+            namespaces.get("inkscape").map(String::as_str),
+            Some("http://www.inkscape.org/namespaces/inkscape")
+        );
+        assert_eq!(
+            namespaces.get("sodipodi").map(String::as_str),
+            Some("http://sodipodi.sourceforge.net/DTD/sodipodi-0.dtd")
+        );
+        assert_eq!(
+            namespaces.get("").map(String::as_str),
+            Some("http://www.w3.org/2000/svg")
+        );
+        assert_eq!(
+            namespaces.keys().collect::<Vec<_>>(),
+            ["", "cc", "dc", "inkscape", "rdf", "sodipodi", "svg"]
+        );
+
+        assert!(Metadata::parse("<svg/>").unwrap().namespaces().is_empty());
+    }
+
+    #[test]
+    fn test_parse_max_depth() {
+        let nested = |depth: usize| {
+            format!(
+                "<svg>{}{}</svg>",
+                "<g>".repeat(depth - 1),
+                "</g>".repeat(depth - 1)
+            )
+        };
+        let options = |max_depth| ParseOptions {
+            max_depth: Some(max_depth),
+            ..ParseOptions::default()
+        };
+
+        // Rejected before building the tree
+        let err = Metadata::parse_with_options(nested(1000), options(100)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MaxDepthExceeded);
+
+        let svg = nested(50);
+        assert!(Metadata::parse_with_options(&svg, options(49)).is_err());
+        assert!(Metadata::parse_with_options(&svg, options(50)).is_ok());
+        assert!(Metadata::parse(&svg).is_ok());
+
+        // Siblings don't add up, and self-closing elements count
+        let svg = r#"<?xml version="1.0"?><!-- <g> --><svg><g/><g/><g><rect/></g>
+            <![CDATA[<g>]]><text a="/>">&lt;g></text></svg>"#;
+        assert!(Metadata::parse_with_options(svg, options(3)).is_ok());
+        assert!(Metadata::parse_with_options(svg, options(2)).is_err());
+        let err = Metadata::parse_with_options("<svg/>", options(0)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MaxDepthExceeded);
+    }
+
+    #[test]
+    fn test_lint() {
+        let codes = |svg: &str| -> Vec<&'static str> {
+            let meta = Metadata::parse(svg).unwrap();
+            meta.lint().into_iter().map(|issue| issue.code).collect()
+        };
+        assert!(codes(r#"<svg viewBox="0 0 10 10" width="10px" height="10px"/>"#).is_empty());
+        assert_eq!(codes("<svg/>"), ["missing-view-box"]);
+        assert_eq!(codes(r#"<html viewBox="0 0 10 10"/>"#), ["non-svg-root"]);
+        assert_eq!(
+            codes(r#"<svg viewBox="0 0 10px 10px"/>"#),
+            ["view-box-has-units"]
+        );
+        assert_eq!(codes(r#"<svg viewBox="0 0 10"/>"#), ["invalid-view-box"]);
+        assert_eq!(
+            codes(r#"<svg viewBox="0 0 -10 10"/>"#),
+            ["negative-view-box"]
+        );
+        assert_eq!(
+            codes(r#"<svg viewBox="0 0 10 10" width="wide" height="10px"/>"#),
+            ["invalid-width"]
+        );
+        assert_eq!(
+            codes(r#"<svg height="50%"/>"#),
+            ["missing-view-box", "percentage-without-view-box"]
+        );
+        assert_eq!(
+            codes(r#"<svg viewBox="0 0 10 10" width="20px" height="10px"/>"#),
+            ["aspect-ratio-mismatch"]
+        );
+        assert!(codes(
+            r#"<svg viewBox="0 0 10 10" width="20px" height="10px" preserveAspectRatio="none"/>"#
+        )
+        .is_empty());
+
+        // Units on the viewBox are reported even if it was recovered
+        let options = ParseOptions {
+            recover: true,
+            ..ParseOptions::default()
+        };
+        let meta =
+            Metadata::parse_with_options(r#"<svg viewBox="0 0 10px 10px"/>"#, options).unwrap();
+        assert!(meta.view_box.is_some());
+        let issues = meta.lint();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Error);
+        assert!(issues[0]
+            .to_string()
+            .starts_with("error[view-box-has-units]: "));
+    }
+
+    #[test]
+    fn test_parse_verbose() {
+        let svg = r#"<svg viewBox="0 0 100 100" width="200px" height="100px"/>"#;
+        let (meta, warnings) = Metadata::parse_verbose(svg).unwrap();
+        assert_eq!(meta, Metadata::parse(svg).unwrap());
+        assert_eq!(
+            warnings,
+            vec![Warning::AspectRatioMismatch { difference: 1.0 }]
+        );
+
+        // Consistent dimensions
+        let svg = r#"<svg viewBox="0 0 100 50" width="2in" height="1in"/>"#;
+        assert!(Metadata::parse_verbose(svg).unwrap().1.is_empty());
+
+        // Intentional distortion
+        let svg = r#"<svg viewBox="0 0 100 100" width="200px" height="100px"
+            preserveAspectRatio="none"/>"#;
+        assert!(Metadata::parse_verbose(svg).unwrap().1.is_empty());
+
+        let svg = r#"<svg viewBox="0 0 96px 105px" width="wide"/>"#;
+        let (meta, warnings) = Metadata::parse_verbose(svg).unwrap();
+        assert_eq!(meta.view_box, None);
+        let names: Vec<_> = warnings
+            .iter()
+            .map(|w| match w {
+                Warning::InvalidAttribute { name, .. } => *name,
+                w => panic!("unexpected warning: {w}"),
+            })
+            .collect();
+        assert_eq!(names, ["viewBox", "width"]);
+        assert!(warnings[0].to_string().contains("viewBox"));
+
+        assert!(Metadata::parse_verbose("<svg").is_err());
+    }
+
+    #[test]
+    fn test_try_parse() {
+        let svg = fs::read_to_string("fixtures/test.svg").unwrap();
+        assert_eq!(Metadata::try_parse(&svg), Metadata::parse(&svg).ok());
+        assert_eq!(Metadata::try_parse(""), None);
+        assert_eq!(Metadata::try_parse("<svg><g></svg>"), None);
+
+        let meta = Metadata::try_parse(r#"<svg width="wide" viewBox="0 0"/>"#).unwrap();
+        assert_eq!(meta.width, None);
+        assert_eq!(meta.view_box, None);
+    }
+
+    #[test]
+    fn test_images() {
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg"
+            xmlns:xlink="http://www.w3.org/1999/xlink">
+          <image xlink:href="old.png"/>
+          <g><image href="new.png"/></g>
+          <image href="both.png" xlink:href="ignored.png"/>
+          <image/>
+          <use xlink:href="sprite.svg#icon"/>
+        </svg>"#;
+        let meta = Metadata::parse(svg).unwrap();
+        assert_eq!(meta.images(), ["old.png", "new.png", "both.png"]);
+
+        // The prefix doesn't matter, only the namespace
+        let svg = r#"<svg xmlns:l="http://www.w3.org/1999/xlink"><image l:href="a.png"/></svg>"#;
+        assert_eq!(Metadata::parse(svg).unwrap().images(), ["a.png"]);
+
+        assert!(Metadata::parse("<svg/>").unwrap().images().is_empty());
+    }
+
+    #[test]
+    fn test_use_refs() {
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg"
+            xmlns:xlink="http://www.w3.org/1999/xlink">
+          <defs><symbol id="star"/><symbol id="moon"/></defs>
+          <use href="#star"/>
+          <g><use xlink:href="#moon" x="10"/></g>
+          <use href="sprite.svg#icon" xlink:href="#ignored"/>
+          <use/>
+          <image href="#not-a-use"/>
+        </svg>"##;
+        let meta = Metadata::parse(svg).unwrap();
+        assert_eq!(meta.use_refs(), ["star", "moon", "sprite.svg#icon"]);
+
+        assert!(Metadata::parse("<svg/>").unwrap().use_refs().is_empty());
+    }
+
+    #[test]
+    fn test_has_raster() {
+        let svg = r#"<svg><g><image href="data:image/png;base64,iVBORw0KGgo="/></g></svg>"#;
+        assert!(Metadata::parse(svg).unwrap().has_raster());
+        assert!(Metadata::parse("<svg><image/></svg>").unwrap().has_raster());
+
+        let svg = r#"<svg><rect width="10" height="10"/><use href="icon.svg"/></svg>"#;
+        assert!(!Metadata::parse(svg).unwrap().has_raster());
+    }
+
+    #[test]
+    fn test_require_dimensions() {
+        let err = Metadata::parse("<svg/>")
+            .unwrap()
+            .require_dimensions()
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NoIntrinsicSize);
+        assert!(err.to_string().contains("width and height"));
+
+        let meta = Metadata::parse(r#"<svg viewBox="0 0 96 105"/>"#).unwrap();
+        assert_eq!(meta.require_dimensions().unwrap(), (96.0, 105.0));
+
+        let meta = Metadata::parse(r#"<svg viewBox="0 0 96 105" width="1in"/>"#).unwrap();
+        assert_eq!(meta.require_dimensions().unwrap(), (96.0, 105.0));
+
+        let err = Metadata::parse(r#"<svg width="10px" height="2em"/>"#)
+            .unwrap()
+            .require_dimensions()
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NoIntrinsicSize);
+        assert!(err.to_string().contains("the height"));
+
+        let meta = Metadata::parse(r#"<svg width="100" height="50"/>"#).unwrap();
+        assert_eq!(meta.require_dimensions().unwrap(), (100.0, 50.0));
+        let meta =
+            Metadata::parse(r#"<svg viewBox="0 0 24 24" width="48" height="2em"/>"#).unwrap();
+        assert_eq!(meta.require_dimensions().unwrap(), (48.0, 24.0));
+
+        // Unresolvable attributes fall back to the viewBox
+        let meta =
+            Metadata::parse(r#"<svg viewBox="0 0 96 105" width="2em" height="3ex"/>"#).unwrap();
+        assert_eq!(meta.require_dimensions().unwrap(), (96.0, 105.0));
+        let err = Metadata::parse(r#"<svg width="2em" height="50"/>"#)
+            .unwrap()
+            .require_dimensions()
+            .unwrap_err();
+        assert!(err.to_string().contains("the width"));
+    }
+
+    #[test]
+    fn test_width_height_scale() {
+        let meta =
+            Metadata::parse(r#"<svg viewBox="0 0 24 24" width="48px" height="72px"/>"#).unwrap();
+        assert_eq!(meta.width_scale(), Some(2.0));
+        assert_eq!(meta.height_scale(), Some(3.0));
+
+        // The common icon markup without units
+        let meta = Metadata::parse(r#"<svg viewBox="0 0 24 24" width="48" height="48"/>"#).unwrap();
+        assert_eq!(meta.width_scale(), Some(2.0));
+        assert_eq!(meta.height_scale(), Some(2.0));
+
+        let meta =
+            Metadata::parse(r#"<svg viewBox="0 0 96 105" width="1in" height="105px"/>"#).unwrap();
+        assert_eq!(meta.width_scale(), Some(1.0));
+        assert_eq!(meta.height_scale(), Some(1.0));
+
+        // Percentages refer to the viewBox
+        let meta = Metadata::parse(r#"<svg viewBox="0 0 24 24" width="50%"/>"#).unwrap();
+        assert_eq!(meta.width_scale(), Some(0.5));
+        assert_eq!(meta.height_scale(), None);
+
+        assert_eq!(
+            Metadata::parse(r#"<svg width="48px"/>"#)
+                .unwrap()
+                .width_scale(),
+            None
+        );
+        let meta = Metadata::parse(r#"<svg viewBox="0 0 0 24" width="48px"/>"#).unwrap();
+        assert_eq!(meta.width_scale(), None);
+        let meta = Metadata::parse(r#"<svg viewBox="0 0 24 24" width="2em"/>"#).unwrap();
+        assert_eq!(meta.width_scale(), None);
+    }
+
+    #[test]
+    fn test_parse_bom() {
+        // A string read without stripping the byte order mark
+        let svg = "\u{feff}<svg viewBox=\"0 0 10 20\" width=\"10px\"><rect/></svg>";
+        let expected = Metadata::parse(&svg['\u{feff}'.len_utf8()..]).unwrap();
+        let meta = Metadata::parse(svg).unwrap();
+        assert_eq!(meta.view_box, expected.view_box);
+        assert_eq!(meta.width, expected.width);
+        assert_eq!(Metadata::parse_strict(svg).unwrap().width, expected.width);
+
+        // Ranges refer to the input as passed, including the mark
+        let range = meta.attribute_ranges().width.clone().unwrap();
+        assert_eq!(&svg[range], "width=\"10px\"");
+
+        let meta = Metadata::parse_prefix(svg, svg.len()).unwrap().unwrap();
+        assert_eq!(meta.view_box, expected.view_box);
+        let truncated = "\u{feff}<?xml version=\"1.0\"?><svg width=\"10px\"><path d=\"M";
+        let meta = Metadata::parse_allow_partial_xml(truncated).unwrap();
+        assert_eq!(meta.width, expected.width);
+    }
+
+    #[test]
+    fn test_resolve_root_font_size() {
+        let meta = Metadata::parse(r#"<svg font-size="20" width="2em" height="1ex"/>"#).unwrap();
+        let options = ResolveOptions::default();
+        assert_eq!(meta.resolve_width(&options), Some(40.0));
+        assert_eq!(meta.resolve_height(&options), Some(10.0));
+
+        // An explicit font size takes precedence
+        let options = ResolveOptions {
+            font_size: Some(10.0),
+            ..ResolveOptions::default()
+        };
+        assert_eq!(meta.resolve_width(&options), Some(20.0));
+
+        let meta = Metadata::parse(r#"<svg font-size="12pt" width="2em"/>"#).unwrap();
+        assert_eq!(meta.resolve_width(&ResolveOptions::default()), Some(32.0));
+
+        // Relative to the unknown parent font size
+        let meta = Metadata::parse(r#"<svg font-size="2em" width="2em"/>"#).unwrap();
+        assert_eq!(meta.resolve_width(&ResolveOptions::default()), None);
+    }
+
+    #[test]
+    fn test_resolve_dimension_source() {
+        let meta = Metadata::parse(r#"<svg viewBox="0 0 24 12" width="48px"/>"#).unwrap();
+        let resolve = |dimension_source| {
+            let options = ResolveOptions {
+                dimension_source,
+                ..ResolveOptions::default()
+            };
+            (meta.resolve_width(&options), meta.resolve_height(&options))
+        };
+        assert_eq!(resolve(DimensionSource::Attributes), (Some(48.0), None));
+        assert_eq!(resolve(DimensionSource::ViewBox), (Some(24.0), Some(12.0)));
+        assert_eq!(
+            resolve(DimensionSource::AttributesThenViewBox),
+            (Some(48.0), Some(12.0))
+        );
+        assert_eq!(
+            resolve(DimensionSource::default()),
+            (
+                meta.resolve_width(&ResolveOptions::default()),
+                meta.resolve_height(&ResolveOptions::default())
+            )
+        );
+
+        // Percentages still refer to the viewBox
+        let meta = Metadata::parse(r#"<svg viewBox="0 0 24 12" height="50%"/>"#).unwrap();
+        let options = ResolveOptions {
+            dimension_source: DimensionSource::Attributes,
+            ..ResolveOptions::default()
+        };
+        assert_eq!(meta.resolve_height(&options), Some(6.0));
+        assert_eq!(meta.resolve_width(&options), None);
+
+        let meta = Metadata::parse(r#"<svg width="48px" height="1em"/>"#).unwrap();
+        let options = ResolveOptions {
+            dimension_source: DimensionSource::ViewBox,
+            ..ResolveOptions::default()
+        };
+        assert_eq!(meta.resolve_width(&options), None);
+    }
+
+    #[test]
+    fn test_resolve_css_default_font_size() {
+        let meta = Metadata::parse(r#"<svg width="2em" height="2ex"/>"#).unwrap();
+        let options = ResolveOptions {
+            use_css_default_font_size: true,
+            ..ResolveOptions::default()
+        };
+        assert_eq!(meta.resolve_width(&options), Some(32.0));
+        assert_eq!(meta.resolve_height(&options), Some(16.0));
+        assert_eq!(meta.resolve_width(&ResolveOptions::default()), None);
+
+        // Explicit and root font sizes take precedence
+        let explicit = ResolveOptions {
+            font_size: Some(10.0),
+            ..options
+        };
+        assert_eq!(meta.resolve_width(&explicit), Some(20.0));
+        let meta = Metadata::parse(r#"<svg width="2em" font-size="20px"/>"#).unwrap();
+        assert_eq!(meta.resolve_width(&options), Some(40.0));
+
+        // A relative root font size refers to the default
+        let meta = Metadata::parse(r#"<svg width="2em" font-size="1.5em"/>"#).unwrap();
+        assert_eq!(meta.resolve_width(&options), Some(48.0));
+        assert_eq!(meta.resolve_width(&ResolveOptions::default()), None);
+    }
+
+    #[test]
+    fn test_resolve_fractional_dpi() {
+        let meta = Metadata::parse(r#"<svg width="1in" height="1cm"/>"#).unwrap();
+        let options = ResolveOptions {
+            dpi: 137.5,
+            ..ResolveOptions::default()
+        };
+        assert_eq!(meta.resolve_width(&options), Some(137.5));
+        assert_eq!(meta.resolve_height(&options), Some(137.5 / 2.54));
+
+        let options = ResolveOptions {
+            round_to_int: true,
+            ..options
+        };
+        assert_eq!(meta.resolve_width(&options), Some(138.0));
+        assert_eq!(meta.resolve_height(&options), Some(54.0));
+
+        let (width, _) = meta.physical_size_mm(137.5).unwrap();
+        assert!((width - 25.4).abs() < 1e-9);
+        assert_eq!(Unit::In.scale_factor(137.5), Some(137.5));
+    }
+
+    #[test]
+    fn test_resolve() {
+        let meta = Metadata::parse(r#"<svg width="1ex" height="2em"/>"#).unwrap();
+        let options = ResolveOptions::default();
+        assert_eq!(meta.resolve_width(&options), None);
+        assert_eq!(meta.resolve_height(&options), None);
+
+        let options = ResolveOptions {
+            font_size: Some(20.0),
+            ..ResolveOptions::default()
+        };
+        assert_eq!(meta.resolve_width(&options), Some(10.0));
+        assert_eq!(meta.resolve_height(&options), Some(40.0));
+
+        let options = ResolveOptions {
+            font_size: Some(20.0),
+            ex_ratio: 0.43,
+            ..ResolveOptions::default()
+        };
+        assert!((meta.resolve_width(&options).unwrap() - 8.6).abs() < 1e-9);
+        assert_eq!(meta.resolve_height(&options), Some(40.0));
+
+        let meta =
+            Metadata::parse(r#"<svg viewBox="0 0 10 20" width="1in" height="50%"/>"#).unwrap();
+        let options = ResolveOptions::default();
+        assert_eq!(meta.resolve_width(&options), Some(96.0));
+        assert_eq!(meta.resolve_height(&options), Some(10.0));
+
+        let meta = Metadata::parse(r#"<svg viewBox="0 0 10 20"/>"#).unwrap();
+        assert_eq!(meta.resolve_width(&options), Some(10.0));
+        assert_eq!(meta.resolve_height(&options), Some(20.0));
+
+        // Lengths without a unit are in user units, regardless of the DPI
+        let meta = Metadata::parse(r#"<svg width="100" height="50.5"/>"#).unwrap();
+        assert_eq!(meta.resolve_width(&options), Some(100.0));
+        assert_eq!(meta.resolve_height(&options), Some(50.5));
+        let options = ResolveOptions {
+            dpi: 300.0,
+            ..ResolveOptions::default()
+        };
+        assert_eq!(meta.resolve_width(&options), Some(100.0));
+
+        let meta = Metadata::parse_file("fixtures/openclipart-desktop_monitor.svg").unwrap();
+        assert_eq!(meta.resolve_width(&options), Some(744.094_488_19));
+    }
+
+    #[test]
+    fn test_canonicalize() {
+        let a = Metadata::parse(r#"<svg viewBox="0 0 96 96" width="1in" height="100%"/>"#).unwrap();
+        let b = Metadata::parse(r#"<svg viewBox="96, 96, -96, -96" width="72pt" height="96px"/>"#)
+            .unwrap();
+        assert_ne!(a, b);
+        assert_eq!(a.canonicalize(96.0), b.canonicalize(96.0));
+
+        let canonical = a.canonicalize(96.0);
+        assert_eq!(
+            canonical.view_box,
+            Some(ViewBox::from_dimensions(96.0, 96.0))
+        );
+        assert_eq!(canonical.width, Some(Width::new(96.0, Unit::Px).unwrap()));
+        assert_eq!(canonical.height, Some(Height::new(96.0, Unit::Px).unwrap()));
+
+        // Lengths without a unit are in pixels
+        let unitless =
+            Metadata::parse(r#"<svg viewBox="0 0 96 96" width="96" height="96"/>"#).unwrap();
+        assert_eq!(unitless.canonicalize(96.0), b.canonicalize(96.0));
+
+        // The DPI matters for absolute units
+        assert_ne!(a.canonicalize(300.0), b.canonicalize(96.0));
+
+        // Font-relative units and missing dimensions stay as they are
+        let meta = Metadata::parse(r#"<svg width="2em"/>"#).unwrap();
+        let canonical = meta.canonicalize(96.0);
+        assert_eq!(canonical.width, meta.width);
+        assert_eq!(canonical.height, None);
+    }
+
+    #[test]
+    fn test_content_hash() {
+        let hash = |svg: &str| Metadata::parse(svg).unwrap().content_hash();
+        let expected = hash(r#"<svg viewBox="0 0 96 105"/>"#);
+        assert_eq!(hash(r#"<svg viewBox="0,0,96,105"/>"#), expected);
+        assert_eq!(hash(r#"<svg viewBox=" 0, 0, 96.0, 105 "/>"#), expected);
+        assert_eq!(hash(r#"<svg viewBox="96 105 -96 -105"/>"#), expected);
+        assert_eq!(
+            hash(r#"<!-- comment --><svg viewBox="0 0 96 105"> <rect/> </svg>"#),
+            expected
+        );
+        assert_eq!(hash(r#"<svg viewBox="0 0 96.0000001 105"/>"#), expected);
+
+        assert_ne!(hash(r#"<svg viewBox="0 0 96 106"/>"#), expected);
+        assert_ne!(hash(r#"<svg viewBox="0 0 96 105" fill="red"/>"#), expected);
+        assert_ne!(hash("<svg/>"), expected);
+        assert_eq!(
+            hash(r#"<svg width="1in" height="1cm"/>"#),
+            hash(r#"<svg width="96px" height="10mm"/>"#)
+        );
+        assert_ne!(hash(r#"<svg fill="ab"/>"#), hash(r#"<svg color="ab"/>"#));
+
+        // The value must not change, as it may be persisted
+        assert_eq!(hash("<svg/>"), 0x2072_5e24_0a15_13dc);
+        assert_eq!(StableHasher::default().0, 0xcbf2_9ce4_8422_2325);
+        let mut hasher = StableHasher::default();
+        hasher.write(b"a");
+        assert_eq!(hasher.0, 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn test_diff() {
+        let left =
+            Metadata::parse(r#"<svg viewBox="0 0 96 105" width="10px" height="2em"/>"#).unwrap();
+        let right = Metadata::parse(r#"<svg viewBox="0, 0, 96, 105" width="1.5in" height="2em"/>"#)
+            .unwrap();
+        assert_eq!(
+            left.diff(&right),
+            vec![FieldDiff {
+                field: "width",
+                left: Some("10px".to_string()),
+                right: Some("1.5in".to_string()),
+            }]
+        );
+        assert!(left.diff(&left).is_empty());
+
+        let right = Metadata::parse(r#"<html fill="red"/>"#).unwrap();
+        let fields: Vec<_> = left.diff(&right).into_iter().map(|d| d.field).collect();
+        assert_eq!(
+            fields,
+            vec!["view_box", "width", "height", "fill", "root_tag"]
+        );
+        assert_eq!(
+            left.diff(&right)[0],
+            FieldDiff {
+                field: "view_box",
+                left: Some("0 0 96 105".to_string()),
+                right: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_aspect_ratio_simplified() {
+        let cases = vec![
+            (r#"<svg width="1920px" height="1080px"/>"#, Some((16, 9))),
+            (r#"<svg viewBox="0 0 1366 768"/>"#, Some((16, 9))),
+            (r#"<svg viewBox="0 0 24 24"/>"#, Some((1, 1))),
+            (r#"<svg viewBox="0 0 105 96"/>"#, Some((12, 11))),
+            (
+                r#"<svg width="2.54cm" height="2in" viewBox="0 0 3 4"/>"#,
+                Some((1, 2)),
+            ),
+            // Units can't be compared, so the viewBox is used instead
+            (
+                r#"<svg width="3em" height="1in" viewBox="0 0 3 4"/>"#,
+                Some((3, 4)),
+            ),
+            (r#"<svg viewBox="0 0 10 0"/>"#, None),
+            ("<svg/>", None),
+        ];
+        for (svg, expected) in cases {
+            let meta = Metadata::parse(svg).unwrap();
+            assert_eq!(meta.aspect_ratio_simplified(), expected, "{svg}");
+        }
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn test_arbitrary() {
+        use arbitrary::{Arbitrary, Unstructured};
+
+        let bytes: Vec<u8> = (0..=255).cycle().take(1024).collect();
+        let mut u = Unstructured::new(&bytes);
+        for _ in 0..10 {
+            let meta = Metadata::arbitrary(&mut u).unwrap();
+            if let Some(v) = meta.view_box {
+                assert!(v.min_x.is_finite() && v.min_y.is_finite());
+                assert!(v.width.is_finite() && v.height.is_finite());
+            }
+            if let Some(w) = meta.width {
+                assert!(w.width.is_finite());
+            }
+            assert_eq!(meta.root_tag(), "svg");
+        }
+    }
+
+    #[test]
+    fn test_parse_open_file() {
+        let mut file = fs::File::open("fixtures/test.svg").unwrap();
+        assert_eq!(
+            Metadata::parse_open_file(&mut file).unwrap(),
+            Metadata::parse_file("fixtures/test.svg").unwrap()
+        );
+
+        // The handle has been read to the end
+        assert!(Metadata::parse_open_file(&mut file).is_err());
+    }
+
+    #[test]
+    fn test_raw_attributes() {
+        let meta =
+            Metadata::parse(r#"<svg viewBox=" 0 0 96 105 " width="1e2px" height="1.0"/>"#).unwrap();
+        assert_eq!(meta.raw_view_box(), Some(" 0 0 96 105 "));
+        assert_eq!(meta.raw_width(), Some("1e2px"));
+        assert_eq!(meta.raw_height(), Some("1.0"));
+
+        let meta = Metadata::parse(r#"<svg width="wide" height="calc(1em + 2px)"/>"#).unwrap();
+        assert_eq!(meta.width, None);
+        assert_eq!(meta.raw_width(), Some("wide"));
+        assert_eq!(meta.height, None);
+        assert_eq!(meta.raw_height(), Some("calc(1em + 2px)"));
+        assert_eq!(meta.raw_view_box(), None);
 
-        let svg_elem = doc.root_element();
-        let view_box = match svg_elem.attribute("viewBox") {
-            Some(val) => ViewBox::try_from(val).ok(),
-            None => None,
-        };
+        // Entities are resolved
+        let meta = Metadata::parse(r#"<svg width="10&#x70;x"/>"#).unwrap();
+        assert_eq!(meta.raw_width(), Some("10px"));
+    }
 
-        let width = match svg_elem.attribute("width") {
-            Some(val) => Width::try_from(val).ok(),
-            None => None,
-        };
+    #[test]
+    fn test_attributes() {
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg"
+            xmlns:xlink="http://www.w3.org/1999/xlink"
+            zoomAndPan="magnify" contentScriptType="text/ecmascript"
+            contentStyleType="text/css" width="10px" xml:space="preserve"
+            xlink:title="Title"/>"#;
+        let meta = Metadata::parse(svg).unwrap();
+        let attributes = meta.attributes();
+        assert_eq!(
+            attributes.get("zoomAndPan").map(String::as_str),
+            Some("magnify")
+        );
+        assert_eq!(
+            attributes.get("contentScriptType").map(String::as_str),
+            Some("text/ecmascript")
+        );
+        assert_eq!(
+            attributes.get("contentStyleType").map(String::as_str),
+            Some("text/css")
+        );
+        assert_eq!(
+            attributes.keys().map(AsRef::as_ref).collect::<Vec<&str>>(),
+            [
+                "contentScriptType",
+                "contentStyleType",
+                "width",
+                "xlink:title",
+                "xml:space",
+                "zoomAndPan"
+            ]
+        );
 
-        let height = match svg_elem.attribute("height") {
-            Some(val) => Height::try_from(val).ok(),
-            None => None,
-        };
+        assert!(Metadata::parse("<svg/>").unwrap().attributes().is_empty());
+        assert!(meta.canonicalize(96.0).attributes().is_empty());
+    }
 
-        Ok(Metadata {
-            view_box,
-            width,
-            height,
-        })
+    #[test]
+    fn test_data_attributes() {
+        let svg = r#"<svg xmlns:app="urn:app" data-icon-name="home" data-="empty"
+            data-Version="2" database="no" app:data-x="namespaced" width="10px"/>"#;
+        let meta = Metadata::parse(svg).unwrap();
+        let data = meta.data_attributes();
+        assert_eq!(
+            data.iter()
+                .map(|(name, value)| (name.as_str(), value.as_str()))
+                .collect::<Vec<_>>(),
+            [("", "empty"), ("Version", "2"), ("icon-name", "home")]
+        );
+
+        let meta = Metadata::parse(r#"<svg data-icon-name="home"/>"#).unwrap();
+        assert_eq!(
+            meta.data_attributes(),
+            BTreeMap::from([("icon-name".to_string(), "home".to_string())])
+        );
+        assert!(Metadata::parse("<svg/>")
+            .unwrap()
+            .data_attributes()
+            .is_empty());
     }
 
-    /// Returns the value of the `width` attribute.
-    /// If the width is set to 100% then this refers to
-    /// the width of the viewbox.
-    #[must_use]
-    pub fn width(&self) -> Option<f64> {
-        if let Some(w) = self.width {
-            if w.unit == Unit::Percent {
-                if let Some(v) = self.view_box {
-                    return Some(w.width / 100.0 * v.width);
+    #[test]
+    fn test_attribute_name_interning() {
+        assert!(COMMON_ATTRIBUTES.windows(2).all(|w| w[0] < w[1]));
+
+        let svg = r#"<svg xmlns:xlink="http://www.w3.org/1999/xlink" viewBox="0 0 1 1"
+            xml:space="preserve" data-icon="x" xlink:title="Title"/>"#;
+        let meta = Metadata::parse(svg).unwrap();
+        let borrowed = |name: &str| {
+            let (key, _) = meta.attributes().get_key_value(name).unwrap();
+            matches!(key, Cow::Borrowed(_))
+        };
+        assert!(borrowed("viewBox"));
+        assert!(borrowed("xml:space"));
+        assert!(!borrowed("data-icon"));
+        assert!(!borrowed("xlink:title"));
+
+        // Most keys in a corpus of real-world images are shared
+        let (mut shared, mut owned) = (0, 0);
+        for entry in fs::read_dir("fixtures").unwrap() {
+            let Ok(meta) = Metadata::parse_file(entry.unwrap().path()) else {
+                continue;
+            };
+            for key in meta.attributes().keys() {
+                match key {
+                    Cow::Borrowed(_) => shared += 1,
+                    Cow::Owned(_) => owned += 1,
                 }
             }
         }
-        self.width.map(|w| w.width)
+        assert!(shared > 10 * owned, "{shared} shared, {owned} owned");
     }
 
-    /// Returns the value of the `height` attribute.
-    /// If the height is set to 100% then this refers to
-    /// the height of the viewbox.
-    #[must_use]
-    pub fn height(&self) -> Option<f64> {
-        if let Some(h) = self.height {
-            if h.unit == Unit::Percent {
-                if let Some(v) = self.view_box {
-                    return Some(h.height / 100.0 * v.height);
-                }
-            }
+    #[test]
+    fn test_single_quoted_attributes() {
+        let double = r#"<svg viewBox="0 0 96 105" width="10px" height="2em" fill="red"/>"#;
+        let single = "<svg viewBox='0 0 96 105' width='10px' height='2em' fill='red'/>";
+        let meta = Metadata::parse(single).unwrap();
+        assert_eq!(meta, Metadata::parse(double).unwrap());
+        assert_eq!(meta, Metadata::parse_strict(single).unwrap());
+
+        let range = meta.attribute_ranges().width.clone().unwrap();
+        assert_eq!(&single[range], "width='10px'");
+    }
+
+    #[cfg(feature = "encoding")]
+    #[test]
+    fn test_decode_declared_encoding_quotes() {
+        let svg = |decl: &str| {
+            let mut data = decl.as_bytes().to_vec();
+            data.extend_from_slice(b"<svg><title>Caf\xe9</title></svg>");
+            crate::decode::decode(data)
+        };
+        let expected = "<svg><title>Caf\u{e9}</title></svg>";
+        for decl in [
+            r#"<?xml version="1.0" encoding="ISO-8859-1"?>"#,
+            "<?xml version='1.0' encoding='ISO-8859-1'?>",
+        ] {
+            assert!(svg(decl).unwrap().ends_with(expected), "{decl}");
         }
-        self.height.map(|h| h.height)
+
+        // Mismatched quotes are not a valid declaration
+        assert!(svg(r#"<?xml version="1.0" encoding="ISO-8859-1'?>"#).is_err());
     }
 
-    /// Return `view_box`
-    #[must_use]
-    pub const fn view_box(&self) -> Option<ViewBox> {
-        self.view_box
+    #[test]
+    fn test_parse_file_auto() {
+        let dir = std::env::temp_dir().join(format!("svg_metadata_auto_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let svg = fs::read("fixtures/test.svg").unwrap();
+        let expected = Metadata::parse_file("fixtures/test.svg").unwrap();
+
+        let path = dir.join("plain.svg");
+        fs::write(&path, &svg).unwrap();
+        assert_eq!(Metadata::parse_file_auto(&path).unwrap(), expected);
+
+        // A compressed file with the wrong extension
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        std::io::Write::write_all(&mut encoder, &svg).unwrap();
+        let path = dir.join("compressed.svg");
+        fs::write(&path, encoder.finish().unwrap()).unwrap();
+        let meta = Metadata::parse_file_auto(&path);
+        if cfg!(feature = "svgz") {
+            assert_eq!(meta.unwrap(), expected);
+        } else {
+            assert!(meta.is_err());
+        }
+        // Plain `parse_file` doesn't detect compression
+        assert!(Metadata::parse_file(&path).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[cfg(feature = "encoding")]
+    #[test]
+    fn test_parse_file_encoding() {
+        let dir =
+            std::env::temp_dir().join(format!("svg_metadata_encoding_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        // "Café" in windows-1252 is not valid UTF-8
+        let latin1 = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?>\n\
+<svg viewBox=\"0 0 10 20\" xmlns=\"http://www.w3.org/2000/svg\"><title>Caf\xe9</title></svg>";
+        let path = dir.join("latin1.svg");
+        fs::write(&path, latin1).unwrap();
+        let meta = Metadata::parse_file(&path).unwrap();
+        assert_eq!(meta.view_box, Some(ViewBox::from_dimensions(10.0, 20.0)));
+
+        let utf16: Vec<u8> = "\u{feff}<svg viewBox=\"0 0 10 20\"/>"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        let path = dir.join("utf16.svg");
+        fs::write(&path, utf16).unwrap();
+        let meta = Metadata::parse_file(&path).unwrap();
+        assert_eq!(meta.view_box, Some(ViewBox::from_dimensions(10.0, 20.0)));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 
     #[test]
-    fn test_view_box_separators() {
-        // Values can be separated by whitespace and/or a comma
-        let cases = vec!["0 1 99 100", "0, 1, 99, 100", "0, 1  99 100"];
-        for case in cases {
-            assert_eq!(
-                ViewBox::try_from(case).unwrap(),
-                ViewBox {
-                    min_x: 0.0,
-                    min_y: 1.0,
-                    width: 99.0,
-                    height: 100.0
-                }
-            );
+    fn test_parse_data() {
+        let svg = br#"<svg viewBox="0 0 10 20"/>"#;
+        let meta = Metadata::parse_data(svg).unwrap();
+        assert_eq!(meta.view_box, Some(ViewBox::from_dimensions(10.0, 20.0)));
+        assert!(Metadata::parse_data(b"<svg \xff/>").is_err());
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        std::io::Write::write_all(&mut encoder, svg).unwrap();
+        let compressed = Metadata::parse_data(&encoder.finish().unwrap());
+        if cfg!(feature = "svgz") {
+            assert_eq!(compressed.unwrap(), meta);
+        } else {
+            assert!(compressed.is_err());
         }
     }
 
+    #[cfg(all(feature = "svgz", feature = "encoding"))]
     #[test]
-    fn test_view_box_negative() {
-        assert_eq!(
-            ViewBox::try_from("-0, 1, -99.00001, -100.3").unwrap(),
-            ViewBox {
-                min_x: 0.0,
-                min_y: 1.0,
-                width: -99.00001,
-                height: -100.3
-            }
-        );
+    fn test_parse_data_compressed_utf16() {
+        let utf16: Vec<u8> = "\u{feff}<svg viewBox=\"0 0 10 20\"><title>Caf\u{e9}</title></svg>"
+            .encode_utf16()
+            .flat_map(u16::to_be_bytes)
+            .collect();
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        std::io::Write::write_all(&mut encoder, &utf16).unwrap();
+        let meta = Metadata::parse_data(&encoder.finish().unwrap()).unwrap();
+        assert_eq!(meta.view_box, Some(ViewBox::from_dimensions(10.0, 20.0)));
     }
 
     #[test]
-    fn test_width() {
-        let tests = vec![
-            (
-                "100em",
-                Width {
-                    width: 100.0,
-                    unit: Unit::Em,
-                },
-            ),
-            (
-                "100",
-                Width {
-                    width: 100.0,
-                    unit: Unit::Em,
-                },
-            ),
+    fn test_error_clone() {
+        let err = Metadata::parse_file("fixtures/does-not-exist.svg").unwrap_err();
+        let cloned = err.clone();
+        assert_eq!(err.to_string(), cloned.to_string());
+    }
+
+    #[test]
+    fn test_error_source() {
+        use std::error::Error;
+
+        let err = Metadata::parse_file("fixtures/does-not-exist.svg").unwrap_err();
+        let source = err.source().unwrap();
+        assert!(source.downcast_ref::<std::io::Error>().is_some());
+
+        let err = Metadata::parse("<svg").unwrap_err();
+        assert!(err.source().unwrap().is::<roxmltree::Error>());
+
+        let err = Metadata::parse_strict(r#"<svg width="-"/>"#).unwrap_err();
+        assert!(err.source().is_none());
+    }
+
+    #[test]
+    fn test_aspect_ratio_mismatch() {
+        let cases = vec![
+            (r#"<svg viewBox="0 0 24 24" width="48" height="48"/>"#, None),
             (
-                "-10.0px",
-                Width {
-                    width: -10.0,
-                    unit: Unit::Px,
-                },
+                r#"<svg viewBox="0 0 20 10" width="4cm" height="2cm"/>"#,
+                None,
             ),
             (
-                "100em",
-                Width {
-                    width: 100.0,
-                    unit: Unit::Em,
-                },
+                r#"<svg viewBox="0 0 100 100" width="1in" height="2.54cm"/>"#,
+                None,
             ),
-        ];
-        for (input, expected) in tests {
-            assert_eq!(Width::try_from(input).unwrap(), expected);
-        }
-    }
-
-    #[test]
-    fn test_height() {
-        let tests = vec![
             (
-                "100em",
-                Height {
-                    height: 100.0,
-                    unit: Unit::Em,
-                },
+                r#"<svg viewBox="0 0 100 100" width="200" height="100"/>"#,
+                Some(1.0),
             ),
             (
-                "100",
-                Height {
-                    height: 100.0,
-                    unit: Unit::Em,
-                },
+                r#"<svg viewBox="0 0 200 100" width="1cm" height="2cm"/>"#,
+                Some(1.5),
             ),
+            // Within the tolerance
             (
-                "-10.0px",
-                Height {
-                    height: -10.0,
-                    unit: Unit::Px,
-                },
+                r#"<svg viewBox="0 0 100 100" width="100.5" height="100"/>"#,
+                None,
             ),
+            // Not resolvable
+            (r#"<svg viewBox="0 0 100 100" width="200"/>"#, None),
             (
-                "100em",
-                Height {
-                    height: 100.0,
-                    unit: Unit::Em,
-                },
+                r#"<svg viewBox="0 0 100 100" width="2em" height="1px"/>"#,
+                None,
             ),
+            (r#"<svg width="200" height="100"/>"#, None),
         ];
-        for (input, expected) in tests {
-            assert_eq!(Height::try_from(input).unwrap(), expected);
+        for (svg, expected) in cases {
+            let meta = Metadata::parse(svg).unwrap();
+            assert_eq!(meta.aspect_ratio_mismatch(0.01), expected, "{svg}");
         }
     }
 
-    #[test]
-    fn test_width_height_percent() {
-        let svg = r#"<svg viewBox="0 1 99 100" width="100%" height="100%" xmlns="http://www.w3.org/2000/svg">
-  <rect x="0" y="0" width="100%" height="100%"/>
-</svg>"#;
-
-        let meta = Metadata::parse(svg).unwrap();
-        assert_eq!(meta.width(), Some(99.0));
-        assert_eq!(meta.height(), Some(100.0));
-
-        let svg = r#"<svg viewBox="0 1 80 200" width="50%" height="20%" xmlns="http://www.w3.org/2000/svg"></svg>"#;
-
-        let meta = Metadata::parse(svg).unwrap();
-        assert_eq!(meta.width(), Some(40.0));
-        assert_eq!(meta.height(), Some(40.0));
-    }
-
     #[test]
     fn test_metadata() {
         // separated by whitespace and/or a comma
@@ -487,6 +6681,263 @@ mod tests {
                 unit: Unit::Cm
             })
         );
+        assert_eq!(meta.root_tag(), "svg");
+    }
+
+    #[test]
+    fn test_fill_color() {
+        let svg = r##"<svg fill="#ff0000" color="currentColor" xmlns="http://www.w3.org/2000/svg">
+  <rect fill="blue" width="10" height="10"/>
+</svg>"##;
+        let meta = Metadata::parse(svg).unwrap();
+        assert_eq!(meta.fill.as_deref(), Some("#ff0000"));
+        assert_eq!(meta.color.as_deref(), Some("currentColor"));
+
+        let meta = Metadata::parse("<svg/>").unwrap();
+        assert_eq!(meta.fill, None);
+        assert_eq!(meta.color, None);
+    }
+
+    #[test]
+    fn test_parse_embedded() {
+        let html = r#"<html xmlns="http://www.w3.org/1999/xhtml">
+  <body>
+    <p>An icon:</p>
+    <div>
+      <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24" width="48px">
+        <circle cx="12" cy="12" r="10"/>
+      </svg>
+    </div>
+  </body>
+</html>"#;
+        let meta = Metadata::parse_embedded(html).unwrap().unwrap();
+        assert_eq!(
+            meta.view_box,
+            Some(ViewBox {
+                min_x: 0.0,
+                min_y: 0.0,
+                width: 24.0,
+                height: 24.0
+            })
+        );
+        assert_eq!(meta.width(), Some(48.0));
+
+        // The root element itself is also considered
+        let meta = Metadata::parse_embedded(r#"<svg width="10"/>"#).unwrap();
+        assert_eq!(meta.unwrap().width(), Some(10.0));
+
+        let html = "<html><body><p>No images here</p></body></html>";
+        assert_eq!(Metadata::parse_embedded(html).unwrap(), None);
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_parse_url() {
+        use std::io::{Read, Write};
+        use std::net::TcpListener;
+
+        /// Serve a single HTTP response for each body on a local port
+        fn serve(bodies: Vec<Vec<u8>>) -> String {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let addr = listener.local_addr().unwrap();
+            std::thread::spawn(move || {
+                for body in bodies {
+                    let (mut stream, _) = listener.accept().unwrap();
+                    let mut request = [0; 1024];
+                    let _ = stream.read(&mut request).unwrap();
+                    write!(
+                        stream,
+                        "HTTP/1.1 200 OK\r\nContent-Type: image/svg+xml\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        body.len()
+                    )
+                    .unwrap();
+                    stream.write_all(&body).unwrap();
+                }
+            });
+            format!("http://{addr}/icon.svg")
+        }
+
+        let svg = br#"<svg viewBox="0 0 24 24" xmlns="http://www.w3.org/2000/svg"/>"#;
+        let url = serve(vec![
+            svg.to_vec(),
+            vec![0x1f, 0x8b, 0x08, 0x00],
+            svg.to_vec(),
+            svg.to_vec(),
+        ]);
+
+        let meta = Metadata::parse_url(&url).unwrap();
+        assert_eq!(
+            meta.view_box,
+            Some(ViewBox {
+                min_x: 0.0,
+                min_y: 0.0,
+                width: 24.0,
+                height: 24.0
+            })
+        );
+        assert!(Metadata::parse_url(&url).is_err());
+        assert!(Metadata::parse_url("http://127.0.0.1:0/unreachable.svg").is_err());
+
+        // The limit is inclusive
+        assert!(Metadata::parse_url_with_limit(&url, svg.len()).is_ok());
+        let err = Metadata::parse_url_with_limit(&url, svg.len() - 1).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InputTooLarge);
+    }
+
+    #[test]
+    fn test_attribute_ranges() {
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg"
+     viewBox="0 0 10 10" width='10cm'>
+  <rect height="5"/>
+</svg>"#;
+        let meta = Metadata::parse(svg).unwrap();
+        let ranges = meta.attribute_ranges();
+        assert_eq!(ranges.width, Some(65..77));
+        assert_eq!(&svg[ranges.width.clone().unwrap()], "width='10cm'");
+        assert_eq!(
+            &svg[ranges.view_box.clone().unwrap()],
+            r#"viewBox="0 0 10 10""#
+        );
+        // Attributes of child elements are not considered
+        assert_eq!(ranges.height, None);
+    }
+
+    #[test]
+    fn test_overflow_clip() {
+        let svg = r#"<svg overflow="visible" clip="rect(0, 10, 10, 0)" xmlns="http://www.w3.org/2000/svg"/>"#;
+        let meta = Metadata::parse(svg).unwrap();
+        assert_eq!(meta.overflow.as_deref(), Some("visible"));
+        assert_eq!(meta.clip.as_deref(), Some("rect(0, 10, 10, 0)"));
+
+        let meta = Metadata::parse("<svg/>").unwrap();
+        assert_eq!(meta.overflow, None);
+        assert_eq!(meta.clip, None);
+    }
+
+    #[test]
+    fn test_required_features() {
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg"
+     requiredFeatures="http://www.w3.org/TR/SVG11/feature#Shape
+                       http://www.w3.org/TR/SVG11/feature#Gradient"
+     requiredExtensions="http://example.org/ext">
+</svg>"#;
+        let meta = Metadata::parse(svg).unwrap();
+        assert_eq!(
+            meta.required_features,
+            vec![
+                "http://www.w3.org/TR/SVG11/feature#Shape",
+                "http://www.w3.org/TR/SVG11/feature#Gradient"
+            ]
+        );
+        assert_eq!(meta.required_extensions, vec!["http://example.org/ext"]);
+
+        let meta = Metadata::parse("<svg/>").unwrap();
+        assert!(meta.required_features.is_empty());
+        assert!(meta.required_extensions.is_empty());
+    }
+
+    #[cfg(feature = "url")]
+    #[test]
+    fn test_parse_with_base_url() {
+        let svg = r#"<svg xmlns:xlink="http://www.w3.org/1999/xlink">
+            <image href="icons/foo.png"/>
+            <image xlink:href="/bar.png"/>
+            <image href="../baz.png"/>
+            <image href="HTTPS://Other.example/qux.png"/>
+            <image href="data:image/png;base64,iVBORw0KGgo="/>
+        </svg>"#;
+        let meta = Metadata::parse_with_base_url(svg, "https://example.com/a/b/c.svg").unwrap();
+        assert_eq!(meta.images().len(), 5);
+        let resolved: Vec<_> = meta
+            .image_refs()
+            .into_iter()
+            .map(|image| image.resolved.unwrap())
+            .collect();
+        assert_eq!(
+            resolved,
+            [
+                "https://example.com/a/b/icons/foo.png",
+                "https://example.com/bar.png",
+                "https://example.com/a/baz.png",
+                "HTTPS://Other.example/qux.png",
+                "data:image/png;base64,iVBORw0KGgo=",
+            ]
+        );
+        assert_eq!(meta.image_refs()[0].href, "icons/foo.png");
+
+        // Without a base URL, only absolute references are resolved
+        let refs = Metadata::parse(svg).unwrap().image_refs();
+        assert_eq!(refs[0].resolved, None);
+        assert_eq!(refs[3].resolved.as_deref(), Some(refs[3].href.as_str()));
+
+        assert!(Metadata::parse_with_base_url(svg, "a/b/c.svg").is_err());
+    }
+
+    #[test]
+    fn test_classes() {
+        let meta = Metadata::parse(r#"<svg class="icon dark large"/>"#).unwrap();
+        assert_eq!(meta.classes(), ["icon", "dark", "large"]);
+
+        let meta = Metadata::parse("<svg class=\"\n  icon\t dark \"/>").unwrap();
+        assert_eq!(meta.classes(), ["icon", "dark"]);
+
+        assert!(Metadata::parse("<svg/>").unwrap().classes().is_empty());
+        assert!(Metadata::parse(r#"<svg class=""/>"#)
+            .unwrap()
+            .classes()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_defined_ids_by_tag() {
+        let svg = r#"<svg id="root" xmlns="http://www.w3.org/2000/svg">
+  <defs>
+    <linearGradient id="fade"><stop offset="0"/></linearGradient>
+    <radialGradient id="glow"/>
+    <linearGradient id="stripes"/>
+    <filter id="shadow"><feGaussianBlur id="blur" stdDeviation="2"/></filter>
+  </defs>
+  <rect id="background" fill="url(#fade)" width="10" height="10"/>
+</svg>"#;
+        let meta = Metadata::parse(svg).unwrap();
+        assert_eq!(
+            meta.defined_ids_by_tag("linearGradient"),
+            vec!["fade", "stripes"]
+        );
+        assert_eq!(meta.defined_ids_by_tag("radialGradient"), vec!["glow"]);
+        assert_eq!(meta.defined_ids_by_tag("filter"), vec!["shadow"]);
+        assert_eq!(meta.defined_ids_by_tag("rect"), vec!["background"]);
+        assert!(meta.defined_ids_by_tag("svg").is_empty());
+        assert!(meta.defined_ids_by_tag("pattern").is_empty());
+    }
+
+    #[test]
+    fn test_self_closing_root() {
+        let meta = Metadata::parse(r#"<svg viewBox="0 0 10 10"/>"#).unwrap();
+        assert_eq!(meta.view_box, Some(ViewBox::from_dimensions(10.0, 10.0)));
+
+        let meta = Metadata::parse(r#"<svg width="10px" />"#).unwrap();
+        assert_eq!(meta.width, Some(Width::new(10.0, Unit::Px).unwrap()));
+
+        let meta = Metadata::parse("<svg\n  height='5cm'\n/>").unwrap();
+        assert_eq!(meta.height, Some(Height::new(5.0, Unit::Cm).unwrap()));
+
+        let svg = r#"<?xml version="1.0"?>
+<!DOCTYPE svg PUBLIC "-//W3C//DTD SVG 1.1//EN" "http://www.w3.org/Graphics/SVG/1.1/DTD/svg11.dtd">
+<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 1 2" width="3" height="4"/>"#;
+        let meta = Metadata::parse(svg).unwrap();
+        assert_eq!(meta.view_box, Some(ViewBox::from_dimensions(1.0, 2.0)));
+        assert_eq!(meta.width(), Some(3.0));
+        assert_eq!(meta.height(), Some(4.0));
+    }
+
+    #[test]
+    fn test_root_tag() {
+        let meta = Metadata::parse(r#"<svg:svg xmlns:svg="http://www.w3.org/2000/svg"/>"#).unwrap();
+        assert_eq!(meta.root_tag(), "svg");
+
+        let meta = Metadata::parse(r#"<?xml version="1.0"?><html width="10"/>"#).unwrap();
+        assert_eq!(meta.root_tag(), "html");
     }
 }
 
@@ -0,0 +1,178 @@
+//! Lightweight scanning of the document prolog and the root start tag,
+//! for cases where building a full DOM is too expensive.
+
+/// The start tag of the root element
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(crate) struct RootTag<'a> {
+    /// The qualified name of the element, e.g. `svg` or `svg:svg`
+    pub(crate) name: &'a str,
+    /// The byte offset of the opening `<`
+    pub(crate) start: usize,
+    /// The byte offset just past the closing `>`
+    pub(crate) end: usize,
+    /// Whether the tag is self-closing (`<svg/>`)
+    pub(crate) self_closing: bool,
+}
+
+/// Find the start tag of the root element in `input`, skipping a byte
+/// order mark, the XML declaration, processing instructions, comments,
+/// and a doctype.
+///
+/// Returns `None` if the input ends before the start tag is complete or
+/// if it doesn't look like XML. Attribute values may be enclosed in
+/// single or double quotes and may contain `>`.
+pub(crate) fn root_tag(input: &str) -> Option<RootTag<'_>> {
+    let bytes = input.as_bytes();
+    // Offsets stay relative to `input`, including the byte order mark
+    let mut pos = if input.starts_with('\u{feff}') {
+        '\u{feff}'.len_utf8()
+    } else {
+        0
+    };
+    loop {
+        while bytes.get(pos)?.is_ascii_whitespace() {
+            pos += 1;
+        }
+        let rest = &input[pos..];
+        if rest.starts_with("<?") {
+            pos += rest.find("?>")? + 2;
+        } else if rest.starts_with("<!--") {
+            pos += rest.find("-->")? + 3;
+        } else if rest.starts_with("<!DOCTYPE") {
+            pos += doctype_len(rest)?;
+        } else if let Some(tag) = rest.strip_prefix('<') {
+            let name_len = tag.find(|c: char| c.is_ascii_whitespace() || c == '/' || c == '>')?;
+            let name = &tag[..name_len];
+            if name.is_empty() {
+                return None;
+            }
+            let len = tag_len(rest)?;
+            return Some(RootTag {
+                name,
+                start: pos,
+                end: pos + len,
+                self_closing: rest[..len].ends_with("/>"),
+            });
+        } else {
+            return None;
+        }
+    }
+}
+
+/// Returns the length of a tag up to and including its closing `>`,
+/// ignoring any `>` within quoted attribute values
+fn tag_len(tag: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, b) in tag.bytes().enumerate() {
+        match (quote, b) {
+            (None, b'"' | b'\'') => quote = Some(b),
+            (Some(q), _) if q == b => quote = None,
+            (None, b'>') => return Some(i + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Returns the length of a doctype declaration, which may contain an
+/// internal subset in brackets
+fn doctype_len(doctype: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut depth = 0_usize;
+    for (i, b) in doctype.bytes().enumerate() {
+        match (quote, b) {
+            (None, b'"' | b'\'') => quote = Some(b),
+            (Some(q), _) if q == b => quote = None,
+            (None, b'[') => depth += 1,
+            (None, b']') => depth = depth.saturating_sub(1),
+            (None, b'>') if depth == 0 => return Some(i + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Returns `true` if elements in `input` are nested deeper than
+/// `max_depth`, where the root element has a depth of 1.
+///
+/// This only counts start and end tags, so it is safe to run before
+/// building a tree, which might exhaust the stack for deeply nested
+/// input. Scanning stops at the first malformed markup, which is left
+/// for the XML parser to report.
+pub(crate) fn depth_exceeds(input: &str, max_depth: usize) -> bool {
+    let mut depth = 0_usize;
+    let mut pos = 0;
+    while let Some(offset) = input[pos..].find('<') {
+        pos += offset;
+        let rest = &input[pos..];
+        let len = if rest.starts_with("<!--") {
+            rest.find("-->").map(|end| end + 3)
+        } else if rest.starts_with("<![CDATA[") {
+            rest.find("]]>").map(|end| end + 3)
+        } else if rest.starts_with("<?") {
+            rest.find("?>").map(|end| end + 2)
+        } else if rest.starts_with("<!") {
+            doctype_len(rest)
+        } else if rest.starts_with("</") {
+            depth = depth.saturating_sub(1);
+            tag_len(rest)
+        } else {
+            let len = tag_len(rest);
+            if let Some(len) = len {
+                if depth + 1 > max_depth {
+                    return true;
+                }
+                if !rest[..len].ends_with("/>") {
+                    depth += 1;
+                }
+            }
+            len
+        };
+        let Some(len) = len else {
+            return false;
+        };
+        pos += len;
+    }
+    false
+}
+
+/// Split the attributes of a start tag into `(name, value)` pairs like
+/// an HTML parser would, e.g. for `width=100 height='50' hidden`.
+///
+/// Values may be unquoted, in which case they end at the next whitespace.
+/// Attributes without a value have an empty one. Entities are not
+/// resolved. `attributes` is the part of the tag between the tag name and
+/// the closing `>` or `/>`.
+pub(crate) fn relaxed_attributes(attributes: &str) -> Vec<(&str, &str)> {
+    let mut result = Vec::new();
+    let mut rest = attributes.trim_start();
+    while !rest.is_empty() {
+        let name_len = rest
+            .find(|c: char| c.is_ascii_whitespace() || c == '=')
+            .unwrap_or(rest.len());
+        let name = &rest[..name_len];
+        rest = rest[name_len..].trim_start();
+        let value = match rest.strip_prefix('=').map(str::trim_start) {
+            Some(value) if value.starts_with(['"', '\'']) => {
+                let quote = &value[..1];
+                // An unterminated value extends to the end of the tag
+                let len = value[1..].find(quote).map_or(value.len(), |len| len + 1);
+                rest = value.get(len + 1..).unwrap_or_default();
+                &value[1..len]
+            }
+            Some(value) => {
+                let len = value
+                    .find(|c: char| c.is_ascii_whitespace())
+                    .unwrap_or(value.len());
+                rest = &value[len..];
+                &value[..len]
+            }
+            None => "",
+        };
+        if !name.is_empty() {
+            result.push((name, value));
+        }
+        rest = rest.trim_start();
+    }
+    result
+}
@@ -0,0 +1,86 @@
+//! Counts the heap allocations of `Metadata::parse` over the fixtures, to
+//! check that interning common attribute names actually saves memory.
+//! This is a separate test binary, as it replaces the global allocator.
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fs;
+
+use svg_metadata::Metadata;
+
+/// Common attribute names which are only stored in the attributes map
+const COMMON: [&str; 4] = ["version", "x", "y", "zoomAndPan"];
+
+/// Names which aren't interned, as a baseline
+const UNCOMMON: [&str; 4] = ["data-version", "data-x", "data-y", "data-zoom"];
+
+thread_local! {
+    /// Allocations of the current thread, so that tests running in
+    /// parallel don't affect each other
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Forwards to the system allocator and counts allocations
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Returns the result of `f` and the number of allocations it made
+fn count_allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    (result, ALLOCATIONS.with(Cell::get) - before)
+}
+
+/// Add attributes with the given names to the root element
+fn with_attributes(svg: &str, names: &[&str]) -> Option<String> {
+    let start = svg.find("<svg")? + "<svg".len();
+    if !svg[start..].starts_with(char::is_whitespace) {
+        return None;
+    }
+    let attributes: String = names.iter().map(|name| format!(" {name}=\"1\"")).collect();
+    Some(format!("{}{attributes}{}", &svg[..start], &svg[start..]))
+}
+
+#[test]
+fn test_attribute_name_interning_allocations() {
+    let (mut interned, mut owned, mut documents) = (0, 0, 0);
+    for entry in fs::read_dir("./fixtures").unwrap() {
+        let Ok(svg) = fs::read_to_string(entry.unwrap().path()) else {
+            continue;
+        };
+        let (Some(common), Some(uncommon)) = (
+            with_attributes(&svg, &COMMON),
+            with_attributes(&svg, &UNCOMMON),
+        ) else {
+            continue;
+        };
+        // Initialize lazily compiled regexes and the like beforehand
+        let _ = Metadata::parse(&svg);
+        // Fixtures which already have one of the attributes are invalid now
+        let (Ok(_), common_allocations) = count_allocations(|| Metadata::parse(&common)) else {
+            continue;
+        };
+        let (Ok(_), uncommon_allocations) = count_allocations(|| Metadata::parse(&uncommon)) else {
+            continue;
+        };
+        interned += common_allocations;
+        owned += uncommon_allocations;
+        documents += 1;
+    }
+
+    assert!(documents > 0);
+    // Every interned name saves exactly one allocation
+    assert_eq!(owned - interned, documents * COMMON.len());
+}